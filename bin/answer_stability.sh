@@ -0,0 +1,29 @@
+#!/bin/bash
+
+# Measure how repeated answers vary, detecting DNS load balancing
+
+usage() {
+    echo "Usage: $0 -d <SQLite Database>" 1>&2;
+    exit 1;
+}
+
+while getopts ":d:" o; do
+    case "${o}" in
+        d)
+            DB=${OPTARG}
+            ;;
+        *)
+            usage
+            ;;
+    esac
+done
+
+container_name="dnsobs-collector";
+dir="$( cd "$( dirname "${BASH_SOURCE[0]}" )" >/dev/null 2>&1 && pwd )";
+data_dir="${dir}/../data/";
+sqlite_db="/data/${DB}"
+docker_args="-v ${data_dir}:/data"
+
+echo "$(date -u +%FT%TZ): Computing answer stability in ${DB}...";
+
+docker run ${docker_args} ${container_name} answer_stability --sqlite-db ${sqlite_db}
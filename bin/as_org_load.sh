@@ -0,0 +1,34 @@
+#!/bin/bash
+
+# Load the CAIDA AS Organizations dataset and map collected ASNs to it
+
+usage() {
+    echo "Usage: $0 -d <SQLite Database> -f <AS Organizations File in resources/>" 1>&2;
+    exit 1;
+}
+
+while getopts ":d:f:" o; do
+    case "${o}" in
+        d)
+            DB=${OPTARG}
+            ;;
+        f)
+            AS2ORG=${OPTARG}
+            ;;
+        *)
+            usage
+            ;;
+    esac
+done
+
+container_name="dnsobs-collector";
+dir="$( cd "$( dirname "${BASH_SOURCE[0]}" )" >/dev/null 2>&1 && pwd )";
+data_dir="${dir}/../data/";
+resources_dir="${dir}/../resources/";
+sqlite_db="/data/${DB}"
+docker_args="-v ${data_dir}:/data -v ${resources_dir}:/resources"
+
+echo "$(date -u +%FT%TZ): Loading AS organisations into ${DB}...";
+
+docker run ${docker_args} ${container_name} as_org_load --sqlite-db ${sqlite_db} \
+                                                        --as2org /resources/${AS2ORG}
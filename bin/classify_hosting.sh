@@ -0,0 +1,32 @@
+#!/bin/bash
+
+# Label each domain with the provider its website is hosted by
+
+usage() {
+    echo "Usage: $0 -d <SQLite Database>" 1>&2;
+    exit 1;
+}
+
+while getopts ":d:" o; do
+    case "${o}" in
+        d)
+            DB=${OPTARG}
+            ;;
+        *)
+            usage
+            ;;
+    esac
+done
+
+container_name="dnsobs-collector";
+dir="$( cd "$( dirname "${BASH_SOURCE[0]}" )" >/dev/null 2>&1 && pwd )";
+data_dir="${dir}/../data/";
+resources_dir="${dir}/../resources/";
+sqlite_db="/data/${DB}"
+fingerprints="/resources/hosting_fingerprints.csv"
+docker_args="-v ${data_dir}:/data -v ${resources_dir}:/resources"
+
+echo "$(date -u +%FT%TZ): Classifying hosting in ${DB}...";
+
+docker run ${docker_args} ${container_name} domain_hosting --fingerprints ${fingerprints} \
+                                                           --sqlite-db ${sqlite_db}
@@ -0,0 +1,34 @@
+#!/bin/bash
+
+# Report where the resolvers queried disagree
+
+usage() {
+    echo "Usage: $0 -d <SQLite Database> -o <Output CSV>" 1>&2;
+    exit 1;
+}
+
+while getopts ":d:o:" o; do
+    case "${o}" in
+        d)
+            DB=${OPTARG}
+            ;;
+        o)
+            OUTPUT=${OPTARG}
+            ;;
+        *)
+            usage
+            ;;
+    esac
+done
+
+container_name="dnsobs-collector";
+dir="$( cd "$( dirname "${BASH_SOURCE[0]}" )" >/dev/null 2>&1 && pwd )";
+data_dir="${dir}/../data/";
+sqlite_db="/data/${DB}"
+output="/data/${OUTPUT}"
+docker_args="-v ${data_dir}:/data"
+
+echo "$(date -u +%FT%TZ): Comparing resolvers in ${DB}...";
+
+docker run ${docker_args} ${container_name} resolver_compare --sqlite-db ${sqlite_db} \
+                                                             --output ${output}
@@ -0,0 +1,38 @@
+#!/bin/bash
+
+# Recompute attribution of collected addresses against MaxMind databases
+
+usage() {
+    echo "Usage: $0 -d <SQLite Database> [-m <MaxMind Database in resources/>]..." 1>&2;
+    exit 1;
+}
+
+while getopts ":d:m:" o; do
+    case "${o}" in
+        d)
+            DB=${OPTARG}
+            ;;
+        m)
+            MMDB_ARGS="${MMDB_ARGS} --mmdb /resources/${OPTARG}"
+            ;;
+        *)
+            usage
+            ;;
+    esac
+done
+
+container_name="dnsobs-collector";
+dir="$( cd "$( dirname "${BASH_SOURCE[0]}" )" >/dev/null 2>&1 && pwd )";
+data_dir="${dir}/../data/";
+resources_dir="${dir}/../resources/";
+sqlite_db="/data/${DB}"
+docker_args="-v ${data_dir}:/data -v ${resources_dir}:/resources"
+
+if [ -z "${MMDB_ARGS}" ]; then
+    MMDB_ARGS="--mmdb /resources/GeoLite2-ASN.tar.gz --mmdb /resources/GeoLite2-City.tar.gz"
+fi
+
+echo "$(date -u +%FT%TZ): Enriching addresses in ${DB}...";
+
+docker run ${docker_args} ${container_name} enrich --sqlite-db ${sqlite_db} \
+                                                   ${MMDB_ARGS}
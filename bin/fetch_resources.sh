@@ -20,6 +20,25 @@ resources=(
 
     # GeoLite 2 ASN database
     https://geolite.maxmind.com/download/geoip/database/GeoLite2-ASN.tar.gz
+
+    # GeoLite 2 City database
+    https://geolite.maxmind.com/download/geoip/database/GeoLite2-City.tar.gz
+
+    # Cloud provider IP ranges. Azure Service Tags are published under a new
+    # URL each week, so are to be downloaded by hand from
+    # https://www.microsoft.com/en-us/download/details.aspx?id=56519
+    https://ip-ranges.amazonaws.com/ip-ranges.json
+    https://www.gstatic.com/ipranges/cloud.json
+    https://docs.cloud.oracle.com/en-us/iaas/tools/public_ip_ranges.json
+    https://www.cloudflare.com/ips-v4
+    https://www.cloudflare.com/ips-v6
+
+    # CAIDA AS Organizations
+    https://publicdata.caida.org/datasets/as-organizations/20191001.as-org2info.txt.gz
+
+    # Team Cymru full bogons
+    https://www.team-cymru.org/Services/Bogons/fullbogons-ipv4.txt
+    https://www.team-cymru.org/Services/Bogons/fullbogons-ipv6.txt
 );
 
 dir="$( cd "$( dirname "${BASH_SOURCE[0]}" )" >/dev/null 2>&1 && pwd )";
@@ -32,7 +51,3 @@ echo "$(date -u +%FT%TZ): Downloading resources to ${resource_dir}...";
 for file in "${resources[@]}"; do
     wget $file -P $resource_dir 2>/dev/null || curl -O $file;
 done
-
-# Unpack everything
-unzip *.zip;
-tar --strip=1 -zxvf *.tar.gz;
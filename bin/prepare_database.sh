@@ -5,15 +5,27 @@
 # The database file MUST be created in the data/ subdirectory.
 
 usage() {
-    echo "Usage: $0 -d <SQLite Database>" 1>&2;
+    echo "Usage: $0 -d <SQLite Database> [-l <List File in resources/>] [-f <List Format>] [-i <List ID>] [-b <Batch Size>]" 1>&2;
     exit 1;
 }
 
-while getopts ":d:" o; do
+while getopts ":d:l:f:i:b:" o; do
     case "${o}" in
         d)
             DB=${OPTARG}
             ;;
+        l)
+            LIST=${OPTARG}
+            ;;
+        f)
+            FORMAT=${OPTARG}
+            ;;
+        i)
+            EXTRA_ARGS="${EXTRA_ARGS} --list-id ${OPTARG}"
+            ;;
+        b)
+            EXTRA_ARGS="${EXTRA_ARGS} --batch-size ${OPTARG}"
+            ;;
         *)
             usage
             ;;
@@ -25,12 +37,14 @@ dir="$( cd "$( dirname "${BASH_SOURCE[0]}" )" >/dev/null 2>&1 && pwd )";
 data_dir="${dir}/../data/";
 resources_dir="${dir}/../resources/";
 
-csv_file="/resources/top-1m.csv";
+csv_file="/resources/${LIST:-top-1m.csv.zip}";
 ps_db="/resources/public_suffix_list.dat";
 sqlite_db="/data/${DB}"
 docker_args="-v ${data_dir}:/data -v ${resources_dir}:/resources"
 
 docker run ${docker_args} ${container_name} database_setup --sqlite-db ${sqlite_db};
 docker run ${docker_args} ${container_name} domain_load --csv ${csv_file} \
+                                                        --format ${FORMAT:-csv} \
                                                         --public-suffix-db ${ps_db} \
-                                                        --sqlite-db ${sqlite_db};
+                                                        --sqlite-db ${sqlite_db} \
+                                                        ${EXTRA_ARGS};
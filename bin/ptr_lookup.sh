@@ -0,0 +1,33 @@
+#!/bin/bash
+
+# Look up the reverse DNS of collected addresses, querying the given resolver
+
+usage() {
+    echo "Usage: $0 -d <SQLite Database> -r <DNS Resolver>" 1>&2;
+    exit 1;
+}
+
+while getopts ":d:r:" o; do
+    case "${o}" in
+        d)
+            DB=${OPTARG}
+            ;;
+        r)
+            DNS=${OPTARG}
+            ;;
+        *)
+            usage
+            ;;
+    esac
+done
+
+container_name="dnsobs-collector";
+dir="$( cd "$( dirname "${BASH_SOURCE[0]}" )" >/dev/null 2>&1 && pwd )";
+data_dir="${dir}/../data/";
+sqlite_db="/data/${DB}"
+docker_args="-v ${data_dir}:/data"
+
+echo "$(date -u +%FT%TZ): Looking up PTR records in ${DB}...";
+
+docker run ${docker_args} ${container_name} ptr_lookup --resolver ${DNS} \
+                                                       --sqlite-db ${sqlite_db}
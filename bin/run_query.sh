@@ -1,19 +1,55 @@
 #!/bin/bash
 
-# Query the given resolver
+# Query the given resolvers
 
 usage() {
-    echo "Usage: $0 -d <SQLite Database> -r <DNS Resolver>" 1>&2;
+    echo "Usage: $0 -d <SQLite Database> -r <DNS Resolver>... [-w] [-s] [-m] [-t] [-n <Repeat Count>] [-e <Client Subnet>]... [-a <ASN Source> -f <ASN File>] [-g <GeoIP File>] [-c <Cloud Provider>:<Ranges File>]... [-b <Bogon File>]... [-v <VRP File>]" 1>&2;
     exit 1;
 }
 
-while getopts ":d:r:" o; do
+while getopts ":d:r:wsmtn:e:a:f:g:c:b:v:" o; do
     case "${o}" in
         d)
             DB=${OPTARG}
             ;;
         r)
-            DNS=${OPTARG}
+            RESOLVER_ARGS="${RESOLVER_ARGS} --resolver ${OPTARG}"
+            ;;
+        w)
+            EXTRA_ARGS="${EXTRA_ARGS} --probe-wildcard"
+            ;;
+        s)
+            EXTRA_ARGS="${EXTRA_ARGS} --dnssec"
+            ;;
+        m)
+            EXTRA_ARGS="${EXTRA_ARGS} --detect-manipulation"
+            ;;
+        t)
+            EXTRA_ARGS="${EXTRA_ARGS} --authoritative-ttl"
+            ;;
+        n)
+            EXTRA_ARGS="${EXTRA_ARGS} --repeat ${OPTARG}"
+            ;;
+        e)
+            EXTRA_ARGS="${EXTRA_ARGS} --ecs-subnet ${OPTARG}"
+            ;;
+        a)
+            ASN_SOURCE=${OPTARG}
+            ;;
+        f)
+            ASN_FILE=${OPTARG}
+            ;;
+        g)
+            EXTRA_ARGS="${EXTRA_ARGS} --geo-db /resources/${OPTARG}"
+            ;;
+        b)
+            EXTRA_ARGS="${EXTRA_ARGS} --bogons /resources/${OPTARG}"
+            ;;
+        v)
+            EXTRA_ARGS="${EXTRA_ARGS} --vrps /resources/${OPTARG}"
+            ;;
+        c)
+            EXTRA_ARGS="${EXTRA_ARGS} --cloud-ranges ${OPTARG%%:*}:/resources/${OPTARG#*:}"
             ;;
         *)
             usage
@@ -27,11 +63,13 @@ dir="$( cd "$( dirname "${BASH_SOURCE[0]}" )" >/dev/null 2>&1 && pwd )";
 data_dir="${dir}/../data/";
 resources_dir="${dir}/../resources/";
 sqlite_db="/data/${DB}"
-asn_db="/resources/GeoLite2-ASN.mmdb"
+asn_db="/resources/${ASN_FILE:-GeoLite2-ASN.tar.gz}"
 docker_args="-v ${data_dir}:/data -v ${resources_dir}:/resources"
 
 echo "$(date -u +%FT%TZ): Querying against ${DB}...";
 
 docker run ${docker_args} ${container_name} domain_query --asn-db ${asn_db} \
-                                                         --resolver ${DNS} \
-                                                         --sqlite-db ${sqlite_db}
+                                                         --asn-source ${ASN_SOURCE:-mmdb} \
+                                                         ${RESOLVER_ARGS} \
+                                                         --sqlite-db ${sqlite_db} \
+                                                         ${EXTRA_ARGS}
@@ -0,0 +1,10 @@
+ALTER TABLE record ADD COLUMN provider TEXT NULL;
+
+CREATE TABLE hosting (
+    domain INTEGER,
+    is_www BOOLEAN NOT NULL CHECK (is_www IN (0,1)),
+    provider TEXT,
+    confidence TEXT,
+    cname_provider TEXT NULL,
+    asn_provider TEXT NULL
+);
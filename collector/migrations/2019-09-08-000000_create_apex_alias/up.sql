@@ -0,0 +1,8 @@
+CREATE TABLE apex_alias (
+    domain INTEGER,
+    cname_target TEXT,
+    classification TEXT,
+    shared_addresses INTEGER,
+    shared_asns INTEGER,
+    query_time INTEGER
+);
@@ -0,0 +1 @@
+DROP TABLE apex_violation;
@@ -0,0 +1,8 @@
+CREATE TABLE apex_violation (
+    domain INTEGER,
+    servers INTEGER,
+    cname_at_apex BOOLEAN NOT NULL CHECK (cname_at_apex IN (0,1)),
+    cname_with_other_data BOOLEAN NOT NULL CHECK (cname_with_other_data IN (0,1)),
+    other_types TEXT NULL,
+    query_time INTEGER
+);
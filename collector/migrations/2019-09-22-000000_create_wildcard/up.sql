@@ -0,0 +1,10 @@
+CREATE TABLE wildcard (
+    domain INTEGER,
+    label TEXT,
+    is_wildcard BOOLEAN NOT NULL CHECK (is_wildcard IN (0,1)),
+    response_code INTEGER,
+    record_type TEXT NULL,
+    ttl INTEGER NULL,
+    address TEXT NULL,
+    query_time INTEGER
+);
@@ -0,0 +1,3 @@
+DROP TABLE dnssec_status;
+DROP INDEX dnssec_record_query;
+DROP TABLE dnssec_record;
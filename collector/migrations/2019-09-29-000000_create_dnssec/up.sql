@@ -0,0 +1,18 @@
+CREATE TABLE dnssec_record (
+    query_name TEXT,
+    query_type TEXT,
+    response_code INTEGER,
+    section TEXT NULL,
+    record_type TEXT NULL,
+    data BLOB NULL,
+    query_time INTEGER
+);
+
+CREATE INDEX dnssec_record_query ON dnssec_record (query_name, query_type);
+
+CREATE TABLE dnssec_status (
+    domain INTEGER,
+    status TEXT,
+    reason TEXT,
+    validation_time INTEGER
+);
@@ -0,0 +1,2 @@
+ALTER TABLE record ADD COLUMN client_subnet TEXT NULL;
+ALTER TABLE record ADD COLUMN scope_prefix INTEGER NULL;
@@ -0,0 +1,63 @@
+-- SQLite before 3.35 cannot drop a column, so each table is rebuilt
+-- without those added, keeping its rowids
+CREATE TABLE dnssec_record_rebuild (
+    query_name TEXT,
+    query_type TEXT,
+    response_code INTEGER,
+    section TEXT NULL,
+    record_type TEXT NULL,
+    data BLOB NULL,
+    query_time INTEGER
+);
+INSERT INTO dnssec_record_rebuild (rowid, query_name, query_type, response_code, section, record_type, data, query_time)
+    SELECT rowid, query_name, query_type, response_code, section, record_type, data, query_time FROM dnssec_record;
+DROP TABLE dnssec_record;
+ALTER TABLE dnssec_record_rebuild RENAME TO dnssec_record;
+CREATE INDEX dnssec_record_query ON dnssec_record (query_name, query_type);
+
+CREATE TABLE apex_violation_rebuild (
+    domain INTEGER,
+    servers INTEGER,
+    cname_at_apex BOOLEAN NOT NULL CHECK (cname_at_apex IN (0,1)),
+    cname_with_other_data BOOLEAN NOT NULL CHECK (cname_with_other_data IN (0,1)),
+    other_types TEXT NULL,
+    query_time INTEGER
+);
+INSERT INTO apex_violation_rebuild (rowid, domain, servers, cname_at_apex, cname_with_other_data, other_types, query_time)
+    SELECT rowid, domain, servers, cname_at_apex, cname_with_other_data, other_types, query_time FROM apex_violation;
+DROP TABLE apex_violation;
+ALTER TABLE apex_violation_rebuild RENAME TO apex_violation;
+
+CREATE TABLE wildcard_rebuild (
+    domain INTEGER,
+    label TEXT,
+    is_wildcard BOOLEAN NOT NULL CHECK (is_wildcard IN (0,1)),
+    response_code INTEGER,
+    record_type TEXT NULL,
+    ttl INTEGER NULL,
+    address TEXT NULL,
+    query_time INTEGER
+);
+INSERT INTO wildcard_rebuild (rowid, domain, label, is_wildcard, response_code, record_type, ttl, address, query_time)
+    SELECT rowid, domain, label, is_wildcard, response_code, record_type, ttl, address, query_time FROM wildcard;
+DROP TABLE wildcard;
+ALTER TABLE wildcard_rebuild RENAME TO wildcard;
+
+CREATE TABLE record_rebuild (
+    domain INTEGER,
+    parent INTEGER NULL,
+    is_www BOOLEAN NOT NULL CHECK (is_www IN (0,1)),
+    response_code INTEGER,
+    record_type TEXT NULL,
+    ttl INTEGER NULL,
+    address TEXT NULL,
+    asn INTEGER NULL,
+    query_time INTEGER,
+    provider TEXT NULL,
+    client_subnet TEXT NULL,
+    scope_prefix INTEGER NULL
+);
+INSERT INTO record_rebuild (rowid, domain, parent, is_www, response_code, record_type, ttl, address, asn, query_time, provider, client_subnet, scope_prefix)
+    SELECT rowid, domain, parent, is_www, response_code, record_type, ttl, address, asn, query_time, provider, client_subnet, scope_prefix FROM record;
+DROP TABLE record;
+ALTER TABLE record_rebuild RENAME TO record;
@@ -0,0 +1,4 @@
+ALTER TABLE record ADD COLUMN resolver TEXT NULL;
+ALTER TABLE wildcard ADD COLUMN resolver TEXT NULL;
+ALTER TABLE apex_violation ADD COLUMN resolver TEXT NULL;
+ALTER TABLE dnssec_record ADD COLUMN resolver TEXT NULL;
@@ -0,0 +1,11 @@
+CREATE TABLE manipulation (
+    domain INTEGER,
+    is_www BOOLEAN NOT NULL CHECK (is_www IN (0,1)),
+    query_type TEXT,
+    resolver TEXT,
+    response_code INTEGER,
+    servers INTEGER,
+    verdict TEXT,
+    reason TEXT NULL,
+    query_time INTEGER
+);
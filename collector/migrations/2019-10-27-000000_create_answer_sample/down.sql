@@ -0,0 +1,2 @@
+DROP TABLE answer_stability;
+DROP TABLE answer_sample;
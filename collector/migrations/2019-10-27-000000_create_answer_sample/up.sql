@@ -0,0 +1,28 @@
+CREATE TABLE answer_sample (
+    domain INTEGER,
+    is_www BOOLEAN NOT NULL CHECK (is_www IN (0,1)),
+    query_type TEXT,
+    resolver TEXT,
+    sample INTEGER,
+    response_code INTEGER,
+    addresses TEXT NULL,
+    ttl INTEGER NULL,
+    query_time INTEGER
+);
+
+CREATE TABLE answer_stability (
+    domain INTEGER,
+    is_www BOOLEAN NOT NULL CHECK (is_www IN (0,1)),
+    query_type TEXT,
+    resolver TEXT,
+    samples INTEGER,
+    distinct_sets INTEGER,
+    distinct_addresses INTEGER,
+    max_set_size INTEGER,
+    reordered BOOLEAN NOT NULL CHECK (reordered IN (0,1)),
+    ttl_min INTEGER NULL,
+    ttl_max INTEGER NULL,
+    ttl_decreasing BOOLEAN NOT NULL CHECK (ttl_decreasing IN (0,1)),
+    balancing TEXT,
+    analysis_time INTEGER
+);
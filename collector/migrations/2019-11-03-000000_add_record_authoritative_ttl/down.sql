@@ -0,0 +1,21 @@
+-- SQLite before 3.35 cannot drop a column, so each table is rebuilt
+-- without those added, keeping its rowids
+CREATE TABLE record_rebuild (
+    domain INTEGER,
+    parent INTEGER NULL,
+    is_www BOOLEAN NOT NULL CHECK (is_www IN (0,1)),
+    response_code INTEGER,
+    record_type TEXT NULL,
+    ttl INTEGER NULL,
+    address TEXT NULL,
+    asn INTEGER NULL,
+    query_time INTEGER,
+    provider TEXT NULL,
+    client_subnet TEXT NULL,
+    scope_prefix INTEGER NULL,
+    resolver TEXT NULL
+);
+INSERT INTO record_rebuild (rowid, domain, parent, is_www, response_code, record_type, ttl, address, asn, query_time, provider, client_subnet, scope_prefix, resolver)
+    SELECT rowid, domain, parent, is_www, response_code, record_type, ttl, address, asn, query_time, provider, client_subnet, scope_prefix, resolver FROM record;
+DROP TABLE record;
+ALTER TABLE record_rebuild RENAME TO record;
@@ -0,0 +1,2 @@
+ALTER TABLE record ADD COLUMN authoritative_ttl INTEGER NULL;
+ALTER TABLE record ADD COLUMN cache_age INTEGER NULL;
@@ -0,0 +1,2 @@
+DROP INDEX ptr_address;
+DROP TABLE ptr;
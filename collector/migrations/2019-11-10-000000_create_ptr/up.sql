@@ -0,0 +1,10 @@
+CREATE TABLE ptr (
+    address TEXT,
+    response_code INTEGER,
+    host TEXT NULL,
+    ttl INTEGER NULL,
+    query_time INTEGER,
+    resolver TEXT
+);
+
+CREATE INDEX ptr_address ON ptr (address);
@@ -0,0 +1,8 @@
+CREATE TABLE asn (
+    number INTEGER,
+    organisation TEXT NULL
+);
+
+CREATE UNIQUE INDEX asn_number ON asn (number);
+
+ALTER TABLE record ADD COLUMN prefix TEXT NULL;
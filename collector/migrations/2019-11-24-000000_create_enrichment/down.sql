@@ -0,0 +1,2 @@
+DROP INDEX enrichment_address;
+DROP TABLE enrichment;
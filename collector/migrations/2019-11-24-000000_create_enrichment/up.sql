@@ -0,0 +1,15 @@
+CREATE TABLE enrichment (
+    address TEXT,
+    database_type TEXT,
+    build_date TEXT,
+    asn INTEGER NULL,
+    organisation TEXT NULL,
+    prefix TEXT NULL,
+    country TEXT NULL,
+    city TEXT NULL,
+    latitude DOUBLE NULL,
+    longitude DOUBLE NULL,
+    enrich_time INTEGER
+);
+
+CREATE INDEX enrichment_address ON enrichment (address, database_type, build_date);
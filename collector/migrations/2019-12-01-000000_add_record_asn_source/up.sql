@@ -0,0 +1 @@
+ALTER TABLE record ADD COLUMN asn_source TEXT NULL;
@@ -0,0 +1,4 @@
+ALTER TABLE record ADD COLUMN country TEXT NULL;
+ALTER TABLE record ADD COLUMN city TEXT NULL;
+ALTER TABLE record ADD COLUMN latitude DOUBLE NULL;
+ALTER TABLE record ADD COLUMN longitude DOUBLE NULL;
@@ -0,0 +1,3 @@
+ALTER TABLE record ADD COLUMN cloud_provider TEXT NULL;
+ALTER TABLE record ADD COLUMN cloud_service TEXT NULL;
+ALTER TABLE record ADD COLUMN cloud_region TEXT NULL;
@@ -0,0 +1,2 @@
+ALTER TABLE record ADD COLUMN special_purpose TEXT NULL;
+ALTER TABLE record ADD COLUMN bogon BOOLEAN NULL CHECK (bogon IN (0,1));
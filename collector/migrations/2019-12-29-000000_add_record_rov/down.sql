@@ -0,0 +1,34 @@
+-- SQLite before 3.35 cannot drop a column, so each table is rebuilt
+-- without those added, keeping its rowids
+CREATE TABLE record_rebuild (
+    domain INTEGER,
+    parent INTEGER NULL,
+    is_www BOOLEAN NOT NULL CHECK (is_www IN (0,1)),
+    response_code INTEGER,
+    record_type TEXT NULL,
+    ttl INTEGER NULL,
+    address TEXT NULL,
+    asn INTEGER NULL,
+    query_time INTEGER,
+    provider TEXT NULL,
+    client_subnet TEXT NULL,
+    scope_prefix INTEGER NULL,
+    resolver TEXT NULL,
+    authoritative_ttl INTEGER NULL,
+    cache_age INTEGER NULL,
+    prefix TEXT NULL,
+    asn_source TEXT NULL,
+    country TEXT NULL,
+    city TEXT NULL,
+    latitude DOUBLE NULL,
+    longitude DOUBLE NULL,
+    cloud_provider TEXT NULL,
+    cloud_service TEXT NULL,
+    cloud_region TEXT NULL,
+    special_purpose TEXT NULL,
+    bogon BOOLEAN NULL CHECK (bogon IN (0,1))
+);
+INSERT INTO record_rebuild (rowid, domain, parent, is_www, response_code, record_type, ttl, address, asn, query_time, provider, client_subnet, scope_prefix, resolver, authoritative_ttl, cache_age, prefix, asn_source, country, city, latitude, longitude, cloud_provider, cloud_service, cloud_region, special_purpose, bogon)
+    SELECT rowid, domain, parent, is_www, response_code, record_type, ttl, address, asn, query_time, provider, client_subnet, scope_prefix, resolver, authoritative_ttl, cache_age, prefix, asn_source, country, city, latitude, longitude, cloud_provider, cloud_service, cloud_region, special_purpose, bogon FROM record;
+DROP TABLE record;
+ALTER TABLE record_rebuild RENAME TO record;
@@ -0,0 +1 @@
+ALTER TABLE record ADD COLUMN rov TEXT NULL;
@@ -0,0 +1,14 @@
+-- SQLite before 3.35 cannot drop a column, so each table is rebuilt
+-- without those added, keeping its rowids
+CREATE TABLE asn_rebuild (
+    number INTEGER,
+    organisation TEXT NULL
+);
+INSERT INTO asn_rebuild (rowid, number, organisation)
+    SELECT rowid, number, organisation FROM asn;
+DROP TABLE asn;
+ALTER TABLE asn_rebuild RENAME TO asn;
+CREATE UNIQUE INDEX asn_number ON asn (number);
+
+DROP INDEX as_org_number;
+DROP TABLE as_org;
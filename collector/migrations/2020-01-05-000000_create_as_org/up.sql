@@ -0,0 +1,13 @@
+CREATE TABLE as_org (
+    number INTEGER,
+    aut_name TEXT NULL,
+    org_id TEXT,
+    org_name TEXT NULL,
+    country TEXT NULL,
+    source TEXT NULL,
+    dataset TEXT
+);
+
+CREATE UNIQUE INDEX as_org_number ON as_org (number);
+
+ALTER TABLE asn ADD COLUMN org_id TEXT NULL;
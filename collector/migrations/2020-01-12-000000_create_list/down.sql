@@ -0,0 +1,4 @@
+DROP INDEX domain_fqdn;
+DROP INDEX list_member_domain;
+DROP TABLE list_member;
+DROP TABLE list;
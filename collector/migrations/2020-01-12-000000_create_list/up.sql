@@ -0,0 +1,20 @@
+CREATE TABLE list (
+    name TEXT,
+    provider TEXT NULL,
+    list_id TEXT NULL,
+    format TEXT,
+    download_date TEXT,
+    file_hash TEXT,
+    load_time INTEGER
+);
+
+CREATE TABLE list_member (
+    list INTEGER,
+    domain INTEGER,
+    rank INTEGER
+);
+
+CREATE INDEX list_member_domain ON list_member (domain);
+
+-- Domains are looked up by name to be shared between lists
+CREATE INDEX domain_fqdn ON domain (fqdn);
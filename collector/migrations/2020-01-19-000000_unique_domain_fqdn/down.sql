@@ -0,0 +1,4 @@
+DROP INDEX list_member_list_domain;
+DROP INDEX list_name;
+DROP INDEX domain_fqdn;
+CREATE INDEX domain_fqdn ON domain (fqdn);
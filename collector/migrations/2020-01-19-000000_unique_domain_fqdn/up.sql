@@ -0,0 +1,45 @@
+-- Domains loaded more than once, by loading a list again, are merged into the
+-- first of them before their FQDN is made unique
+CREATE TEMPORARY TABLE domain_duplicate AS
+    SELECT d.rowid AS duplicate, f.original
+    FROM domain d
+    JOIN (SELECT fqdn, MIN(rowid) AS original FROM domain GROUP BY fqdn) f
+        ON d.fqdn = f.fqdn
+    WHERE d.rowid != f.original;
+
+UPDATE list_member SET domain = (SELECT original FROM domain_duplicate WHERE duplicate = domain)
+    WHERE domain IN (SELECT duplicate FROM domain_duplicate);
+UPDATE record SET domain = (SELECT original FROM domain_duplicate WHERE duplicate = domain)
+    WHERE domain IN (SELECT duplicate FROM domain_duplicate);
+UPDATE hosting SET domain = (SELECT original FROM domain_duplicate WHERE duplicate = domain)
+    WHERE domain IN (SELECT duplicate FROM domain_duplicate);
+UPDATE apex_alias SET domain = (SELECT original FROM domain_duplicate WHERE duplicate = domain)
+    WHERE domain IN (SELECT duplicate FROM domain_duplicate);
+UPDATE apex_violation SET domain = (SELECT original FROM domain_duplicate WHERE duplicate = domain)
+    WHERE domain IN (SELECT duplicate FROM domain_duplicate);
+UPDATE wildcard SET domain = (SELECT original FROM domain_duplicate WHERE duplicate = domain)
+    WHERE domain IN (SELECT duplicate FROM domain_duplicate);
+UPDATE dnssec_status SET domain = (SELECT original FROM domain_duplicate WHERE duplicate = domain)
+    WHERE domain IN (SELECT duplicate FROM domain_duplicate);
+UPDATE manipulation SET domain = (SELECT original FROM domain_duplicate WHERE duplicate = domain)
+    WHERE domain IN (SELECT duplicate FROM domain_duplicate);
+UPDATE answer_sample SET domain = (SELECT original FROM domain_duplicate WHERE duplicate = domain)
+    WHERE domain IN (SELECT duplicate FROM domain_duplicate);
+UPDATE answer_stability SET domain = (SELECT original FROM domain_duplicate WHERE duplicate = domain)
+    WHERE domain IN (SELECT duplicate FROM domain_duplicate);
+
+DELETE FROM domain WHERE rowid IN (SELECT duplicate FROM domain_duplicate);
+DROP TABLE domain_duplicate;
+
+-- Of lists loaded more than once under the same name only the latest load is
+-- kept, its details and members alike, and a domain listed more than once in
+-- a load is ranked by its first appearance
+DELETE FROM list_member WHERE list NOT IN (SELECT MAX(rowid) FROM list GROUP BY name);
+DELETE FROM list WHERE rowid NOT IN (SELECT MAX(rowid) FROM list GROUP BY name);
+DELETE FROM list_member WHERE rowid NOT IN (
+    SELECT MIN(rowid) FROM list_member GROUP BY list, domain);
+
+DROP INDEX domain_fqdn;
+CREATE UNIQUE INDEX domain_fqdn ON domain (fqdn);
+CREATE UNIQUE INDEX list_name ON list (name);
+CREATE UNIQUE INDEX list_member_list_domain ON list_member (list, domain);
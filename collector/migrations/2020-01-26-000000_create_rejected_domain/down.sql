@@ -0,0 +1,2 @@
+DROP INDEX rejected_domain_list;
+DROP TABLE rejected_domain;
@@ -0,0 +1,9 @@
+CREATE TABLE rejected_domain (
+    list INTEGER,
+    line INTEGER,
+    raw TEXT,
+    reason TEXT,
+    error TEXT NULL
+);
+
+CREATE INDEX rejected_domain_list ON rejected_domain (list);
@@ -0,0 +1 @@
+DROP INDEX hosting_domain;
@@ -0,0 +1,5 @@
+-- Classifying hosting again labelled each domain once more, so only the latest
+-- label of each domain is kept
+DELETE FROM hosting WHERE rowid NOT IN (SELECT MAX(rowid) FROM hosting GROUP BY domain);
+
+CREATE UNIQUE INDEX hosting_domain ON hosting (domain);
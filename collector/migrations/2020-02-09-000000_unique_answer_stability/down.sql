@@ -0,0 +1 @@
+DROP INDEX answer_stability_key;
@@ -0,0 +1,7 @@
+-- Computing answer stability again added a further row for each name, so only
+-- the latest is kept
+DELETE FROM answer_stability WHERE rowid NOT IN (
+    SELECT MAX(rowid) FROM answer_stability GROUP BY domain, is_www, query_type, resolver
+);
+
+CREATE UNIQUE INDEX answer_stability_key ON answer_stability (domain, is_www, query_type, resolver);
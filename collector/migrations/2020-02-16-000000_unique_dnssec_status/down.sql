@@ -0,0 +1 @@
+DROP INDEX dnssec_status_domain;
@@ -0,0 +1,5 @@
+-- Validating again added a further status for each domain, so only the latest
+-- is kept
+DELETE FROM dnssec_status WHERE rowid NOT IN (SELECT MAX(rowid) FROM dnssec_status GROUP BY domain);
+
+CREATE UNIQUE INDEX dnssec_status_domain ON dnssec_status (domain);
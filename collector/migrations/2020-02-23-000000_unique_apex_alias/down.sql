@@ -0,0 +1 @@
+DROP INDEX apex_alias_domain;
@@ -0,0 +1,5 @@
+-- Classifying apexes again added a further row for each domain, so only the
+-- latest is kept
+DELETE FROM apex_alias WHERE rowid NOT IN (SELECT MAX(rowid) FROM apex_alias GROUP BY domain);
+
+CREATE UNIQUE INDEX apex_alias_domain ON apex_alias (domain);
@@ -0,0 +1,102 @@
+use std::collections::HashSet;
+
+/// How the apex of a zone is configured relative to the target of the `www`
+/// CNAME. As a CNAME cannot be used at the apex alongside the SOA and NS
+/// records, operators must find other means of pointing the apex at the same
+/// hosting as `www`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ApexClass {
+    /// The apex answers with addresses also returned for the CNAME target,
+    /// indicative of an ALIAS/ANAME record or CNAME flattening.
+    Flattened,
+    /// The apex answers with different addresses, but within the same ASN as
+    /// the CNAME target - likely a static address given by the same provider.
+    SameProvider,
+    /// The apex answers with addresses in an entirely different ASN to the
+    /// CNAME target, e.g. a redirect service.
+    DifferentProvider,
+    /// The apex has no A or AAAA records at all.
+    Missing,
+    /// The CNAME target did not resolve, so no comparison can be made.
+    Unresolved,
+    /// The CNAME target could not be queried, every attempt failing.
+    Failed,
+}
+
+impl ApexClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApexClass::Flattened => "flattened",
+            ApexClass::SameProvider => "same_provider",
+            ApexClass::DifferentProvider => "different_provider",
+            ApexClass::Missing => "missing",
+            ApexClass::Unresolved => "unresolved",
+            ApexClass::Failed => "failed",
+        }
+    }
+}
+
+/// The A/AAAA addresses of a name along with the ASNs they are announced from.
+#[derive(Debug, Default)]
+pub struct AddressSet {
+    pub addresses: HashSet<String>,
+    pub asns: HashSet<i32>,
+}
+
+impl AddressSet {
+    /// Add an address and, if known, its ASN to the set
+    ///
+    /// # Arguments
+    /// * `address` - IP address, as stored in `record.address`
+    /// * `asn` - Autonomous System Number of the address
+    pub fn insert(&mut self, address: &str, asn: Option<i32>) {
+        self.addresses.insert(address.to_string());
+        if let Some(a) = asn {
+            self.asns.insert(a);
+        }
+    }
+
+    /// Number of addresses present in both sets
+    pub fn shared_addresses(&self, other: &AddressSet) -> usize {
+        self.addresses.intersection(&other.addresses).count()
+    }
+
+    /// Number of ASNs present in both sets
+    pub fn shared_asns(&self, other: &AddressSet) -> usize {
+        self.asns.intersection(&other.asns).count()
+    }
+}
+
+/// Compare the addresses of the apex with those of the `www` CNAME target.
+///
+/// # Arguments
+/// * `apex` - Addresses the apex resolves to
+/// * `target` - Addresses the CNAME target of `www` resolves to
+///
+/// ```
+/// use damp::apex::{classify_apex, AddressSet, ApexClass};
+///
+/// let mut apex = AddressSet::default();
+/// let mut target = AddressSet::default();
+/// target.insert("192.0.2.1", Some(64496));
+/// assert_eq!(classify_apex(&apex, &target), ApexClass::Missing);
+///
+/// apex.insert("192.0.2.2", Some(64496));
+/// assert_eq!(classify_apex(&apex, &target), ApexClass::SameProvider);
+///
+/// apex.insert("192.0.2.1", Some(64496));
+/// assert_eq!(classify_apex(&apex, &target), ApexClass::Flattened);
+/// ```
+pub fn classify_apex(apex: &AddressSet, target: &AddressSet) -> ApexClass {
+    if apex.addresses.is_empty() {
+        ApexClass::Missing
+    } else if target.addresses.is_empty() {
+        ApexClass::Unresolved
+    } else if apex.shared_addresses(target) > 0 {
+        ApexClass::Flattened
+    } else if apex.shared_asns(target) > 0 {
+        ApexClass::SameProvider
+    } else {
+        ApexClass::DifferentProvider
+    }
+}
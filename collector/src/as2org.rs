@@ -0,0 +1,116 @@
+use crate::asn::stored_asn;
+use failure::Error;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+
+/// An Autonomous System and the organisation it is registered to.
+#[derive(Debug, PartialEq)]
+pub struct AsOrganisation {
+    /// Autonomous System Number, e.g. 15169
+    pub asn: i32,
+    /// Name of the AS, e.g. "GOOGLE"
+    pub aut_name: Option<String>,
+    /// Identifier of the organisation, shared by its sibling ASNs, e.g.
+    /// "GOGL-ARIN"
+    pub org_id: String,
+    /// Name of the organisation, e.g. "Google LLC"
+    pub org_name: Option<String>,
+    /// ISO 3166-1 code of the country the organisation is registered in
+    pub country: Option<String>,
+    /// The RIR or WHOIS database the registration came from, e.g. "ARIN"
+    pub source: Option<String>,
+}
+
+/// Parse a CAIDA AS Organizations dataset file from disk.
+///
+/// # Arguments
+/// * `path` - Path to the `as-org2info.txt` file, which may be compressed
+pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Vec<AsOrganisation>, Error> {
+    let file = crate::compress::open(path)?;
+    from_reader(file)
+}
+
+/// Parse a CAIDA AS Organizations dataset. The file is pipe separated in two
+/// sections, each started by a comment giving its format - the organisations
+/// followed by the ASNs that refer to them:
+///
+/// ```text
+/// # format:org_id|changed|org_name|country|source
+/// # format:aut|changed|aut_name|org_id|opaque_id|source
+/// ```
+///
+/// ASNs are returned in the order of the file, those referring to an unknown
+/// organisation being given the `org_id` alone. ASNs above `i32::MAX`,
+/// rejected by `asn::stored_asn`, are skipped.
+///
+/// See Also: [CAIDA AS Organizations Dataset](https://www.caida.org/catalog/datasets/as-organizations/)
+///
+/// ```
+/// use damp::as2org::from_reader;
+///
+/// let data = "# format:org_id|changed|org_name|country|source\n\
+///             GOGL-ARIN|20190912|Google LLC|US|ARIN\n\
+///             # format:aut|changed|aut_name|org_id|opaque_id|source\n\
+///             15169|20120224|GOOGLE|GOGL-ARIN|f7b8_ARIN|ARIN\n\
+///             36040|20120224|YOUTUBE|GOGL-ARIN|f7b8_ARIN|ARIN\n\
+///             4200000000|20190101|PRIVATE|GOGL-ARIN|f7b8_ARIN|ARIN\n";
+/// let orgs = from_reader(data.as_bytes()).unwrap();
+/// assert_eq!(orgs.len(), 2);
+/// assert_eq!(orgs[1].asn, 36040);
+/// assert_eq!(orgs[1].org_id, "GOGL-ARIN");
+/// assert_eq!(orgs[1].org_name, Some("Google LLC".to_string()));
+/// ```
+pub fn from_reader<R: Read>(reader: R) -> Result<Vec<AsOrganisation>, Error> {
+    // Organisation id to name and country
+    let mut orgs: HashMap<String, (String, String)> = HashMap::new();
+    let mut auts: Vec<AsOrganisation> = Vec::new();
+    let mut section = "";
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        if line.starts_with("# format:org_id") {
+            section = "org";
+            continue;
+        } else if line.starts_with("# format:aut") {
+            section = "aut";
+            continue;
+        } else if line.starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split('|').collect();
+        match (section, fields.len()) {
+            ("org", 5) => {
+                orgs.insert(
+                    fields[0].to_string(),
+                    (fields[2].to_string(), fields[3].to_string()),
+                );
+            }
+            ("aut", 6) => {
+                let asn = match fields[0].parse().ok().and_then(stored_asn) {
+                    Some(a) => a,
+                    None => continue,
+                };
+                let non_empty = |s: &str| Some(s.to_string()).filter(|s| !s.is_empty());
+                auts.push(AsOrganisation {
+                    asn,
+                    aut_name: non_empty(fields[2]),
+                    org_id: fields[3].to_string(),
+                    org_name: None,
+                    country: None,
+                    source: non_empty(fields[5]),
+                });
+            }
+            _ => return Err(failure::format_err!("Unexpected line '{}'", line)),
+        }
+    }
+
+    for aut in &mut auts {
+        if let Some((name, country)) = orgs.get(&aut.org_id) {
+            aut.org_name = Some(name.clone()).filter(|s| !s.is_empty());
+            aut.country = Some(country.clone()).filter(|s| !s.is_empty());
+        }
+    }
+    Ok(auts)
+}
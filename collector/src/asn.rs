@@ -0,0 +1,457 @@
+use crate::compress;
+use crate::mmdb::{self, build_date, SearchTree};
+use failure::Error;
+use maxminddb::geoip2::Isp;
+use maxminddb::Reader;
+use std::convert::TryFrom;
+use std::io::{BufRead, BufReader, Read};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::Path;
+
+/// The ASN an address is announced from, as found by an [AsnSource].
+#[derive(Debug, Default, PartialEq)]
+pub struct AsnMatch {
+    /// Autonomous System Number of the origin
+    pub asn: i32,
+    /// Name of the organisation operating the AS, where the source knows it
+    pub organisation: Option<String>,
+    /// The network matched, in CIDR notation
+    pub prefix: Option<String>,
+}
+
+/// A means of attributing an IP address to the Autonomous System announcing
+/// it. Sources are shared between the threads of each resolver queried.
+pub trait AsnSource: Send + Sync {
+    /// Find the ASN of the address, or `None` where it is not covered.
+    fn lookup(&self, address: IpAddr) -> Option<AsnMatch>;
+
+    /// Description of the source recorded alongside each attribution, e.g.
+    /// "mmdb:GeoLite2-ASN/2019-11-19".
+    fn name(&self) -> &str;
+
+    /// Whether the prefixes matched are routes announced in BGP, rather than
+    /// the networks of a database which may aggregate or split them.
+    fn announced(&self) -> bool {
+        false
+    }
+}
+
+/// Open the source of the given kind.
+///
+/// # Arguments
+/// * `kind` - One of "mmdb", "prefix2as" or "mrt"
+/// * `path` - Path to the file of the source
+pub fn open_source(kind: &str, path: &str) -> Result<Box<dyn AsnSource>, Error> {
+    match kind {
+        "mmdb" => Ok(Box::new(MmdbSource::from_path(path)?)),
+        "prefix2as" => Ok(Box::new(TrieSource::from_prefix2as(path)?)),
+        "mrt" => Ok(Box::new(TrieSource::from_mrt(path)?)),
+        _ => Err(failure::format_err!("Unknown ASN source '{}'", kind)),
+    }
+}
+
+/// The ASN as stored in the `i32` columns of the schema, or `None` for a
+/// 4 byte ASN above `i32::MAX`, which would otherwise wrap to a negative
+/// number. Those are only the private use and reserved ranges
+/// ([RFC 6996](https://tools.ietf.org/html/rfc6996)), never publicly routed,
+/// so are rejected rather than stored.
+///
+/// ```
+/// use damp::asn::stored_asn;
+///
+/// assert_eq!(stored_asn(396982), Some(396982));
+/// assert_eq!(stored_asn(4_200_000_000), None);
+/// ```
+pub fn stored_asn(asn: u32) -> Option<i32> {
+    i32::try_from(asn).ok()
+}
+
+/// The network containing the address, in CIDR notation.
+///
+/// ```
+/// use damp::asn::cidr;
+///
+/// assert_eq!(cidr("192.0.2.77".parse().unwrap(), 24), "192.0.2.0/24");
+/// assert_eq!(cidr("2001:db8::1".parse().unwrap(), 32), "2001:db8::/32");
+/// ```
+pub fn cidr(address: IpAddr, length: usize) -> String {
+    let (bits, value) = key(address);
+    let mask = match length {
+        0 => 0,
+        l => !0u128 << (bits - l.min(bits)),
+    };
+    let network = match address {
+        IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::from((value & mask) as u32)),
+        IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::from(value & mask)),
+    };
+    format!("{}/{}", network, length)
+}
+
+/// The bit length and value of the address.
+fn key(address: IpAddr) -> (usize, u128) {
+    match address {
+        IpAddr::V4(ip) => (32, u128::from(u32::from(ip))),
+        IpAddr::V6(ip) => (128, u128::from(ip)),
+    }
+}
+
+/// File name of the path, used to describe the source.
+fn file_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|f| f.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// A MaxMind GeoLite2 ASN database.
+pub struct MmdbSource {
+    reader: Reader<Vec<u8>>,
+    search_tree: SearchTree,
+    name: String,
+}
+
+impl MmdbSource {
+    pub fn from_path(path: &str) -> Result<MmdbSource, Error> {
+        let (reader, search_tree) = mmdb::open(path)?;
+        let name = format!(
+            "mmdb:{}/{}",
+            reader.metadata.database_type,
+            build_date(&reader.metadata)
+        );
+        Ok(MmdbSource {
+            reader,
+            search_tree,
+            name,
+        })
+    }
+}
+
+impl AsnSource for MmdbSource {
+    fn lookup(&self, address: IpAddr) -> Option<AsnMatch> {
+        let isp = self.reader.lookup::<Isp>(address).ok()?;
+        Some(AsnMatch {
+            asn: stored_asn(isp.autonomous_system_number?)?,
+            organisation: isp.autonomous_system_organization,
+            prefix: self.search_tree.prefix(address),
+        })
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// A binary trie of IPv4 and IPv6 prefixes, for longest prefix matching.
+pub struct PrefixTrie<T> {
+    v4: Vec<Node<T>>,
+    v6: Vec<Node<T>>,
+}
+
+/// Node of a [PrefixTrie], its children being indices of other nodes - as
+/// the root is never a child, 0 denotes there being none.
+struct Node<T> {
+    children: [u32; 2],
+    value: Option<T>,
+}
+
+impl<T> Node<T> {
+    fn new() -> Node<T> {
+        Node {
+            children: [0, 0],
+            value: None,
+        }
+    }
+}
+
+impl<T> Default for PrefixTrie<T> {
+    fn default() -> PrefixTrie<T> {
+        PrefixTrie {
+            v4: vec![Node::new()],
+            v6: vec![Node::new()],
+        }
+    }
+}
+
+impl<T> PrefixTrie<T> {
+    pub fn new() -> PrefixTrie<T> {
+        PrefixTrie::default()
+    }
+
+    /// Add a prefix, replacing the value of the same prefix if present.
+    ///
+    /// # Arguments
+    /// * `network` - Address of the network
+    /// * `length` - Length of the prefix
+    /// * `value` - Value to return for addresses within the prefix
+    pub fn insert(&mut self, network: IpAddr, length: usize, value: T) {
+        let (bits, key) = key(network);
+        let nodes = match network {
+            IpAddr::V4(_) => &mut self.v4,
+            IpAddr::V6(_) => &mut self.v6,
+        };
+        let mut node = 0;
+        for depth in 0..length.min(bits) {
+            let bit = ((key >> (bits - 1 - depth)) & 1) as usize;
+            node = match nodes[node].children[bit] {
+                0 => {
+                    nodes.push(Node::new());
+                    let child = nodes.len() - 1;
+                    nodes[node].children[bit] = child as u32;
+                    child
+                }
+                child => child as usize,
+            };
+        }
+        nodes[node].value = Some(value);
+    }
+
+    /// The longest prefix containing the address, in CIDR notation, along
+    /// with its value.
+    ///
+    /// ```
+    /// use damp::asn::PrefixTrie;
+    ///
+    /// let mut trie = PrefixTrie::new();
+    /// trie.insert("192.0.0.0".parse().unwrap(), 16, 1);
+    /// trie.insert("192.0.2.0".parse().unwrap(), 24, 2);
+    /// let found = trie.longest_match("192.0.2.1".parse().unwrap());
+    /// assert_eq!(found, Some(("192.0.2.0/24".to_string(), &2)));
+    /// let found = trie.longest_match("192.0.3.1".parse().unwrap());
+    /// assert_eq!(found, Some(("192.0.0.0/16".to_string(), &1)));
+    /// assert_eq!(trie.longest_match("198.51.100.1".parse().unwrap()), None);
+    /// ```
+    pub fn longest_match(&self, address: IpAddr) -> Option<(String, &T)> {
+        let (bits, key) = key(address);
+        let nodes = match address {
+            IpAddr::V4(_) => &self.v4,
+            IpAddr::V6(_) => &self.v6,
+        };
+        let mut node = 0;
+        let mut found = nodes[0].value.as_ref().map(|v| (0, v));
+        for depth in 0..bits {
+            let bit = ((key >> (bits - 1 - depth)) & 1) as usize;
+            node = match nodes[node].children[bit] {
+                0 => break,
+                child => child as usize,
+            };
+            if let Some(v) = &nodes[node].value {
+                found = Some((depth + 1, v));
+            }
+        }
+        found.map(|(length, v)| (cidr(address, length), v))
+    }
+
+    /// Every prefix covering the given prefix - those of the same or a shorter
+    /// length containing it - along with its length and value.
+    ///
+    /// ```
+    /// use damp::asn::PrefixTrie;
+    ///
+    /// let mut trie = PrefixTrie::new();
+    /// trie.insert("192.0.0.0".parse().unwrap(), 16, 1);
+    /// trie.insert("192.0.2.0".parse().unwrap(), 24, 2);
+    /// trie.insert("192.0.2.128".parse().unwrap(), 25, 3);
+    /// let found = trie.covering("192.0.2.0".parse().unwrap(), 24);
+    /// assert_eq!(found, vec![(16, &1), (24, &2)]);
+    /// ```
+    pub fn covering(&self, network: IpAddr, length: usize) -> Vec<(usize, &T)> {
+        let (bits, key) = key(network);
+        let nodes = match network {
+            IpAddr::V4(_) => &self.v4,
+            IpAddr::V6(_) => &self.v6,
+        };
+        let mut node = 0;
+        let mut found: Vec<(usize, &T)> = nodes[0].value.iter().map(|v| (0, v)).collect();
+        for depth in 0..length.min(bits) {
+            let bit = ((key >> (bits - 1 - depth)) & 1) as usize;
+            node = match nodes[node].children[bit] {
+                0 => break,
+                child => child as usize,
+            };
+            if let Some(v) = &nodes[node].value {
+                found.push((depth + 1, v));
+            }
+        }
+        found
+    }
+}
+
+/// Prefixes and their origin ASN loaded into a [PrefixTrie], from either a
+/// CAIDA RouteViews prefix2as file or an MRT RIB dump.
+pub struct TrieSource {
+    trie: PrefixTrie<i32>,
+    name: String,
+}
+
+impl TrieSource {
+    /// Load a CAIDA RouteViews prefix2as file, one prefix per line of
+    /// address, length and origin separated by tabs:
+    ///
+    /// ```text
+    /// 1.0.0.0	24	13335
+    /// 1.0.4.0	22	38803_56203
+    /// ```
+    ///
+    /// Where a prefix has several origins (joined by `_`) or an AS set
+    /// (joined by `,`), the first ASN is taken. Prefixes whose origin is
+    /// rejected by [stored_asn] are skipped.
+    ///
+    /// See Also: [CAIDA Routeviews Prefix to AS mappings](https://www.caida.org/catalog/datasets/routeviews-prefix2as/)
+    pub fn from_prefix2as(path: &str) -> Result<TrieSource, Error> {
+        let mut trie = PrefixTrie::new();
+        for line in BufReader::new(compress::open(path)?).lines() {
+            let line = line?;
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() != 3 {
+                continue;
+            }
+            let network: IpAddr = match fields[0].parse() {
+                Ok(n) => n,
+                Err(_) => continue,
+            };
+            let length: usize = match fields[1].parse() {
+                Ok(l) => l,
+                Err(_) => continue,
+            };
+            let origin = fields[2]
+                .split(|c| c == '_' || c == ',')
+                .next()
+                .unwrap_or("");
+            if let Some(asn) = origin.parse::<u32>().ok().and_then(stored_asn) {
+                trie.insert(network, length, asn);
+            }
+        }
+        Ok(TrieSource {
+            trie,
+            name: format!("prefix2as:{}", file_name(path)),
+        })
+    }
+
+    /// Load an MRT TABLE_DUMP_V2 RIB dump, such as those published by
+    /// RouteViews and RIPE RIS, which may be compressed as any file read by
+    /// [compress::open] - including the `.bz2` RouteViews publish. The origin
+    /// of each prefix is taken from the AS_PATH of its first RIB entry,
+    /// prefixes whose origin is rejected by [stored_asn] being skipped.
+    ///
+    /// See Also: [RFC 6396](https://tools.ietf.org/html/rfc6396)
+    pub fn from_mrt(path: &str) -> Result<TrieSource, Error> {
+        let mut trie = PrefixTrie::new();
+        let mut reader = BufReader::new(compress::open(path)?);
+        let mut header = [0u8; 12];
+        loop {
+            match reader.read_exact(&mut header) {
+                Ok(_) => {}
+                Err(ref e) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e.into()),
+            }
+            let mrt_type = be(&header[4..6]);
+            let subtype = be(&header[6..8]);
+            let mut body = vec![0u8; be(&header[8..12])];
+            reader.read_exact(&mut body)?;
+
+            // TABLE_DUMP_V2 RIB_IPV4_UNICAST and RIB_IPV6_UNICAST
+            let ipv6 = match (mrt_type, subtype) {
+                (13, 2) => false,
+                (13, 4) => true,
+                _ => continue,
+            };
+            if let Some((network, length, asn)) = parse_rib(&body, ipv6) {
+                if let Some(asn) = stored_asn(asn) {
+                    trie.insert(network, length, asn);
+                }
+            }
+        }
+        Ok(TrieSource {
+            trie,
+            name: format!("mrt:{}", file_name(path)),
+        })
+    }
+}
+
+impl AsnSource for TrieSource {
+    fn lookup(&self, address: IpAddr) -> Option<AsnMatch> {
+        let (prefix, asn) = self.trie.longest_match(address)?;
+        Some(AsnMatch {
+            asn: *asn,
+            organisation: None,
+            prefix: Some(prefix),
+        })
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn announced(&self) -> bool {
+        true
+    }
+}
+
+/// Read a big-endian unsigned integer.
+fn be(bytes: &[u8]) -> usize {
+    bytes.iter().fold(0, |acc, b| (acc << 8) | *b as usize)
+}
+
+/// Parse the prefix of a RIB record and the origin ASN of its first entry.
+fn parse_rib(body: &[u8], ipv6: bool) -> Option<(IpAddr, usize, u32)> {
+    let length = *body.get(4)? as usize;
+    let prefix_bytes = (length + 7) / 8;
+    let prefix = body.get(5..5 + prefix_bytes)?;
+    let network = match ipv6 {
+        false => {
+            let mut octets = [0u8; 4];
+            octets.get_mut(..prefix_bytes)?.copy_from_slice(prefix);
+            IpAddr::V4(Ipv4Addr::from(octets))
+        }
+        true => {
+            let mut octets = [0u8; 16];
+            octets.get_mut(..prefix_bytes)?.copy_from_slice(prefix);
+            IpAddr::V6(Ipv6Addr::from(octets))
+        }
+    };
+
+    // Entry count, then each entry of peer index, originated time and
+    // attributes - only the first entry is needed
+    let mut offset = 5 + prefix_bytes;
+    if be(body.get(offset..offset + 2)?) == 0 {
+        return None;
+    }
+    offset += 2 + 2 + 4;
+    let attributes_length = be(body.get(offset..offset + 2)?);
+    offset += 2;
+    let attributes = body.get(offset..offset + attributes_length)?;
+    Some((network, length, as_path_origin(attributes)?))
+}
+
+/// Find the origin - the last ASN - of the AS_PATH among BGP path attributes,
+/// which in a TABLE_DUMP_V2 RIB are always encoded with 4 byte ASNs.
+fn as_path_origin(attributes: &[u8]) -> Option<u32> {
+    let mut offset = 0;
+    while offset + 2 < attributes.len() {
+        let flags = attributes[offset];
+        let attribute_type = attributes[offset + 1];
+        let (length, header) = match flags & 0x10 {
+            0 => (be(attributes.get(offset + 2..offset + 3)?), 3),
+            _ => (be(attributes.get(offset + 2..offset + 4)?), 4),
+        };
+        let value = attributes.get(offset + header..offset + header + length)?;
+        offset += header + length;
+        if attribute_type != 2 {
+            continue;
+        }
+
+        // Segments of type, count and ASNs
+        let mut origin = None;
+        let mut position = 0;
+        while position + 2 <= value.len() {
+            let count = value[position + 1] as usize;
+            let asns = value.get(position + 2..position + 2 + count * 4)?;
+            if let Some(last) = asns.chunks(4).last() {
+                origin = Some(be(last) as u32);
+            }
+            position += 2 + count * 4;
+        }
+        return origin;
+    }
+    None
+}
@@ -0,0 +1,208 @@
+use failure::Error;
+use std::net::{IpAddr, SocketAddr};
+use trust_dns::client::{Client, SyncClient};
+use trust_dns::op::{DnsResponse, Message};
+use trust_dns::rr::{DNSClass, Name, RData, RecordType};
+use trust_dns::udp::UdpClientConnection;
+
+/// Compare two names, ignoring case and whether either is fully qualified.
+///
+/// ```
+/// use damp::authority::same_name;
+/// use trust_dns::rr::Name;
+///
+/// let a = Name::from_ascii("Example.COM.").unwrap();
+/// let b = Name::from_ascii("example.com").unwrap();
+/// assert!(same_name(&a, &b));
+/// ```
+pub fn same_name(a: &Name, b: &Name) -> bool {
+    let a = a.to_ascii().to_ascii_lowercase();
+    let b = b.to_ascii().to_ascii_lowercase();
+    a.trim_end_matches('.') == b.trim_end_matches('.')
+}
+
+/// Send a single query directly to a name server on port 53, bypassing any
+/// resolver and its cache.
+///
+/// # Arguments
+/// * `server` - IP address of the name server
+/// * `name` - Name to query
+/// * `query_type` - The DNS RecordType to query
+pub fn query_server(
+    server: IpAddr,
+    name: &Name,
+    query_type: RecordType,
+) -> Result<DnsResponse, Error> {
+    let conn = UdpClientConnection::new(SocketAddr::new(server, 53))?;
+    let client = SyncClient::new(conn);
+    Ok(client.query(name, DNSClass::IN, query_type)?)
+}
+
+/// Resolve the host names to their IPv4 and IPv6 addresses via the resolver.
+///
+/// # Arguments
+/// * `resolver` - Client of the resolver in use
+/// * `hosts` - Host names to resolve
+pub fn resolve_hosts(resolver: &SyncClient<UdpClientConnection>, hosts: &[Name]) -> Vec<IpAddr> {
+    let mut addresses: Vec<IpAddr> = Vec::new();
+    for host in hosts {
+        for query_type in &[RecordType::A, RecordType::AAAA] {
+            if let Ok(response) = resolver.query(host, DNSClass::IN, *query_type) {
+                let answers = response.answers().iter();
+                addresses.extend(answers.filter_map(|a| a.rdata().to_ip_addr()));
+            }
+        }
+    }
+    addresses.sort();
+    addresses.dedup();
+    addresses
+}
+
+/// The TTL of the record set of the given name and type in the answer
+/// section. As all records of a set share the same TTL, the first found is
+/// returned.
+///
+/// # Arguments
+/// * `response` - Response to search
+/// * `name` - Owner name of the record set
+/// * `record_type` - Type of the record set
+pub fn rrset_ttl(response: &Message, name: &Name, record_type: RecordType) -> Option<u32> {
+    response
+        .answers()
+        .iter()
+        .find(|r| r.record_type() == record_type && same_name(r.name(), name))
+        .map(|r| r.ttl())
+}
+
+/// Send a query to each of the servers in turn, returning the first
+/// authoritative response. Referrals and errors are passed over.
+///
+/// # Arguments
+/// * `servers` - Addresses of the zone's authoritative servers
+/// * `name` - Name to query
+/// * `query_type` - The DNS RecordType to query
+pub fn authoritative_answer(
+    servers: &[IpAddr],
+    name: &Name,
+    query_type: RecordType,
+) -> Option<Message> {
+    servers
+        .iter()
+        .filter_map(|s| query_server(*s, name, query_type).ok())
+        .map(Message::from)
+        .find(|m| m.authoritative())
+}
+
+/// Find the addresses of the name servers authoritative for a zone.
+///
+/// Rather than trusting the NS set returned by the resolver - which may well
+/// be replaced by a CNAME when one is (mis-)configured at the apex - the
+/// delegation is taken from the parent zone's name servers, which will answer
+/// with a referral containing the NS set of the child. Should the parent
+/// provide no referral, the NS set as seen by the resolver is used instead.
+///
+/// # Arguments
+/// * `resolver` - Client of the resolver in use
+/// * `zone` - Apex of the zone
+pub fn authoritative_servers(
+    resolver: &SyncClient<UdpClientConnection>,
+    zone: &Name,
+) -> Result<Vec<IpAddr>, Error> {
+    let parent = zone.base_name();
+    let parent_hosts = ns_hosts(&resolver.query(&parent, DNSClass::IN, RecordType::NS)?, &parent);
+
+    for parent_server in resolve_hosts(resolver, &parent_hosts) {
+        let referral = match query_server(parent_server, zone, RecordType::NS) {
+            Ok(r) => r,
+            Err(_) => continue,
+        };
+        let hosts = ns_hosts(&referral, zone);
+        if !hosts.is_empty() {
+            return Ok(resolve_hosts(resolver, &hosts));
+        }
+    }
+
+    let hosts = ns_hosts(&resolver.query(zone, DNSClass::IN, RecordType::NS)?, zone);
+    Ok(resolve_hosts(resolver, &hosts))
+}
+
+/// Collect the host names of NS records owned by the zone from both the
+/// answer and authority sections of a response.
+fn ns_hosts(response: &DnsResponse, zone: &Name) -> Vec<Name> {
+    response
+        .answers()
+        .iter()
+        .chain(response.name_servers().iter())
+        .filter(|r| same_name(r.name(), zone))
+        .filter_map(|r| match r.rdata() {
+            RData::NS(host) => Some(host.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Result of querying the authoritative name servers of a zone for the data
+/// present at its apex, having seen a CNAME there.
+#[derive(Debug, Default)]
+pub struct ApexViolation {
+    /// Count of authoritative servers that answered
+    pub servers: usize,
+    /// An authoritative server answered with a CNAME owned by the apex
+    pub cname_at_apex: bool,
+    /// Record types other than CNAME owned by the apex, e.g. SOA and NS
+    pub other_types: Vec<RecordType>,
+}
+
+impl ApexViolation {
+    /// A CNAME must not coexist with any other data at the same name
+    /// ([RFC 1034 &sect; 3.6.2](https://tools.ietf.org/html/rfc1034#section-3.6.2)),
+    /// which is always the case at an apex with its SOA and NS records.
+    pub fn cname_with_other_data(&self) -> bool {
+        self.cname_at_apex && !self.other_types.is_empty()
+    }
+}
+
+/// Query every authoritative server of the zone directly for CNAME, SOA, NS,
+/// MX and TXT at the apex, recording whether a CNAME is present and what
+/// other record types coexist with it. As with `authoritative_answer`, only
+/// responses with the AA bit set are considered - a referral or a cached
+/// answer says nothing of the zone's own data.
+///
+/// # Arguments
+/// * `servers` - Addresses of the zone's authoritative servers
+/// * `zone` - Apex of the zone
+pub fn check_apex(servers: &[IpAddr], zone: &Name) -> ApexViolation {
+    let query_types = [
+        RecordType::CNAME,
+        RecordType::SOA,
+        RecordType::NS,
+        RecordType::MX,
+        RecordType::TXT,
+    ];
+    let mut violation = ApexViolation::default();
+
+    for server in servers {
+        let mut answered = false;
+        for query_type in &query_types {
+            let response = match query_server(*server, zone, *query_type) {
+                Ok(r) if r.authoritative() => r,
+                _ => continue,
+            };
+            answered = true;
+            for record in response.answers().iter().filter(|r| same_name(r.name(), zone)) {
+                match record.record_type() {
+                    RecordType::CNAME => violation.cname_at_apex = true,
+                    t => {
+                        if !violation.other_types.contains(&t) {
+                            violation.other_types.push(t);
+                        }
+                    }
+                }
+            }
+        }
+        if answered {
+            violation.servers += 1;
+        }
+    }
+    violation
+}
@@ -0,0 +1,141 @@
+extern crate clap;
+extern crate damp;
+extern crate diesel;
+extern crate failure;
+
+use clap::{App, Arg};
+use damp::model::answer::{AnswerSample, NewAnswerStability};
+use damp::model::connect;
+use damp::stability::{Sample, Stability};
+use damp::*;
+use diesel::prelude::*;
+use failure::Error;
+use std::collections::BTreeMap;
+
+static STABILITY_VERSION: &'static str = env!("CARGO_PKG_VERSION");
+static STABILITY_AUTHORS: &'static str = env!("CARGO_PKG_AUTHORS");
+static STABILITY_ABOUT: &'static str = r#"
+Computes how the answers vary for each name queried repeatedly by the
+domain_query(8) binary with --repeat, identifying DNS-based load balancing.
+
+For each name, query type and resolver the distinct addresses and address sets
+seen are counted, along with whether the order of the same set changed and the
+range of TTLs, and the name classified as:
+* unanswered  - no addresses were answered
+* single      - the same, single address every time
+* static      - the same addresses in the same order every time
+* round_robin - the same addresses, but in a changing order
+* rotating    - the addresses themselves changed
+
+The sample positions restart from 0 on each run of domain_query(8), so only
+the samples of its latest run of each name are used, metrics computed by an
+earlier run of answer_stability being replaced. A summary of load balancing
+at the apex versus www is printed on completion.
+"#;
+
+/// Samples of a single name, keyed by domain, is_www, query type and resolver.
+type SampleKey = (i64, bool, String, String);
+
+/// Position of the last sample of a name read, and the samples of its latest
+/// run.
+type Series = (i32, Vec<Sample>);
+
+fn main() -> Result<(), Error> {
+    let matches = App::new("answer_stability")
+        .version(STABILITY_VERSION)
+        .author(STABILITY_AUTHORS)
+        .about(STABILITY_ABOUT)
+        .arg(
+            Arg::with_name("sqlite-db")
+                .help("Path to SQLite database")
+                .long("sqlite-db")
+                .required(true)
+                .value_name("FILE"),
+        )
+        .get_matches();
+
+    let sqlite_db = matches.value_of("sqlite-db").unwrap();
+
+    // --------------------------
+    //     Start of processing
+    // --------------------------
+    let start = start_processing_marker(format!("Computing answer stability in {}", sqlite_db));
+
+    let conn = connect(sqlite_db.to_string());
+    let rows = {
+        use damp::schema::answer_sample::dsl::*;
+        answer_sample
+            .order(rowid.asc())
+            .load::<AnswerSample>(&conn)?
+    };
+
+    let mut series: BTreeMap<SampleKey, Series> = BTreeMap::new();
+    for row in rows {
+        let key = (row.domain, row.is_www, row.query_type, row.resolver);
+        let (last, samples) = series.entry(key).or_insert_with(|| (0, Vec::new()));
+
+        // Samples are read in the order they were inserted, so a position no
+        // later than the last starts a new run of domain_query
+        if !samples.is_empty() && row.sample <= *last {
+            samples.clear();
+        }
+        *last = row.sample;
+        samples.push(Sample {
+            addresses: match row.addresses {
+                Some(a) => a.split(',').map(String::from).collect(),
+                None => Vec::new(),
+            },
+            ttl: row.ttl,
+        });
+    }
+
+    // Count of names load balanced and answered, for the apex and www
+    let mut balanced = [0; 2];
+    let mut answered = [0; 2];
+    let analysis_time = unix_time();
+    for ((domain, is_www, query_type, resolver), (_, samples)) in &series {
+        let stability = Stability::from_samples(samples);
+        if stability.distinct_addresses > 0 {
+            answered[*is_www as usize] += 1;
+        }
+        if stability.balancing.is_load_balanced() {
+            balanced[*is_www as usize] += 1;
+        }
+
+        let row = NewAnswerStability {
+            domain,
+            is_www,
+            query_type,
+            resolver,
+            samples: &(stability.samples as i32),
+            distinct_sets: &(stability.distinct_sets as i32),
+            distinct_addresses: &(stability.distinct_addresses as i32),
+            max_set_size: &(stability.max_set_size as i32),
+            reordered: &stability.reordered,
+            ttl_min: stability.ttl_min.as_ref(),
+            ttl_max: stability.ttl_max.as_ref(),
+            ttl_decreasing: &stability.ttl_decreasing,
+            balancing: stability.balancing.as_str(),
+            analysis_time: &analysis_time,
+        };
+        match diesel::replace_into(schema::answer_stability::table)
+            .values(&row)
+            .execute(&conn)
+        {
+            Ok(_) => (),
+            Err(e) => eprintln!("Error inserting answer stability - {}", e),
+        };
+    }
+
+    println!(
+        "Load balanced: apex {} of {}, www {} of {}",
+        balanced[0], answered[0], balanced[1], answered[1]
+    );
+
+    // --------------------------
+    //       End of processing
+    // --------------------------
+    end_processing_marker("Computation completed", start);
+
+    Ok(())
+}
@@ -0,0 +1,271 @@
+extern crate clap;
+extern crate damp;
+extern crate diesel;
+extern crate failure;
+
+use clap::{App, Arg};
+use damp::apex::{classify_apex, AddressSet, ApexClass};
+use damp::asn::{open_source, AsnSource};
+use damp::model::apex_alias::NewApexAlias;
+use damp::model::connect;
+use damp::model::domain::Domain;
+use damp::model::record::Record;
+use damp::*;
+use diesel::prelude::*;
+use failure::Error;
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use trust_dns::client::{Client, SyncClient};
+use trust_dns::op::DnsResponse;
+use trust_dns::rr::{DNSClass, Name, RecordType};
+use trust_dns::udp::UdpClientConnection;
+
+static ALIAS_VERSION: &'static str = env!("CARGO_PKG_VERSION");
+static ALIAS_AUTHORS: &'static str = env!("CARGO_PKG_AUTHORS");
+static ALIAS_ABOUT: &'static str = r#"
+Determines what has been done at the apex of each domain whose www name is a
+CNAME, using a SQLite database already populated by the domain_query(8) binary.
+
+The final target of the www CNAME chain is resolved for A and AAAA against the
+provided resolver and its addresses and ASNs compared with the apex addresses
+already collected, classifying the apex as:
+* flattened          - shares addresses with the target (ALIAS/ANAME, flattening)
+* same_provider      - different addresses, but in the same ASN as the target
+* different_provider - addresses in an unrelated ASN
+* missing            - no A or AAAA records at the apex
+* unresolved         - the CNAME target did not resolve
+* failed             - the CNAME target could not be queried
+
+Only answers collected without an EDNS Client Subnet are compared, taking the
+www CNAME chain of the latest www query and the apex addresses returned by the
+same resolver. The classification of a domain by an earlier run is replaced.
+"#;
+
+/// Attempts made to query the CNAME target before it is recorded as failed
+const QUERY_ATTEMPTS: u32 = 3;
+
+struct ApexAlias {
+    dns_client: SyncClient<UdpClientConnection>,
+    sql_client: SqliteConnection,
+    asn_source: Box<dyn AsnSource>,
+}
+
+impl ApexAlias {
+    /// Returns a struct that handles all the various network and DB calls.
+    ///
+    /// # Arguments:
+    /// * `dns_resolver`: The socket address to a Do53 service
+    /// * `sql_db`: Path to SQLite database
+    /// * `asn_source`: Source of the ASN of addresses
+    pub fn new(
+        dns_resolver: SocketAddr,
+        sql_db: &str,
+        asn_source: Box<dyn AsnSource>,
+    ) -> Result<ApexAlias, Error> {
+        let dns_conn = UdpClientConnection::new(dns_resolver).expect("Unable to use DNS client!");
+        Ok(ApexAlias {
+            dns_client: SyncClient::new(dns_conn),
+            sql_client: connect(sql_db.to_string()),
+            asn_source,
+        })
+    }
+
+    /// Classify the apex of every domain with a CNAME at www.
+    pub fn process_all(&self) {
+        use damp::schema::domain::dsl::*;
+        let domains = domain.load::<Domain>(&self.sql_client).unwrap();
+
+        for d in &domains {
+            match self.classify_domain(d) {
+                Ok(_) => (),
+                Err(e) => eprintln!("Unable to classify apex of {} - {}", d.fqdn, e),
+            }
+        }
+    }
+
+    fn classify_domain(&self, d: &Domain) -> Result<(), Error> {
+        use damp::schema::record::dsl::*;
+        let records = record
+            .filter(domain.eq(d.rowid))
+            .order(rowid.asc())
+            .load::<Record>(&self.sql_client)?;
+
+        let is_cname = |r: &&Record| r.record_type.as_ref().map(String::as_str) == Some("CNAME");
+
+        // Answers vary with the EDNS Client Subnet, the resolver and over
+        // time, so a single response is taken - the latest to a www query
+        // made without a subnet, the first row of which heads the chain
+        let records: Vec<&Record> = records
+            .iter()
+            .filter(|r| r.client_subnet.is_none())
+            .collect();
+        let first = match records
+            .iter()
+            .filter(|r| r.is_www && r.parent.is_none())
+            .filter(is_cname)
+            .last()
+        {
+            Some(r) => *r,
+            None => return Ok(()),
+        };
+
+        // The last CNAME in the www chain is the name actually hosting content
+        let target = match records
+            .iter()
+            .filter(|r| r.rowid == first.rowid || r.parent == Some(first.rowid))
+            .filter(is_cname)
+            .filter_map(|r| r.address.clone())
+            .last()
+        {
+            Some(t) => t,
+            None => return Ok(()),
+        };
+        let records: Vec<&Record> = records
+            .into_iter()
+            .filter(|r| r.resolver == first.resolver)
+            .collect();
+
+        // Exclude addresses of the name servers, which are children of NS records
+        let ns_rows: HashSet<i64> = records
+            .iter()
+            .filter(|r| r.record_type.as_ref().map(String::as_str) == Some("NS"))
+            .map(|r| r.rowid)
+            .collect();
+        let mut apex = AddressSet::default();
+        for r in records
+            .iter()
+            .filter(|r| !r.is_www)
+            .filter(|r| r.parent.map_or(true, |p| !ns_rows.contains(&p)))
+        {
+            match (r.record_type.as_ref().map(String::as_str), &r.address) {
+                (Some("A"), Some(a)) | (Some("AAAA"), Some(a)) => apex.insert(a, r.asn),
+                _ => {}
+            }
+        }
+
+        let query_time = unix_time();
+        let (class, target_set) = match self.resolve(&target) {
+            Ok(set) => (classify_apex(&apex, &set), set),
+            Err(e) => {
+                eprintln!("Unable to resolve {} for {} - {}", target, d.fqdn, e);
+                (ApexClass::Failed, AddressSet::default())
+            }
+        };
+
+        let alias = NewApexAlias {
+            domain: &d.rowid,
+            cname_target: &target,
+            classification: class.as_str(),
+            shared_addresses: &(apex.shared_addresses(&target_set) as i32),
+            shared_asns: &(apex.shared_asns(&target_set) as i32),
+            query_time: &query_time,
+        };
+        diesel::replace_into(schema::apex_alias::table)
+            .values(&alias)
+            .execute(&self.sql_client)?;
+        Ok(())
+    }
+
+    /// Resolve A and AAAA for the name, returning the addresses found and
+    /// their ASNs.
+    ///
+    /// # Arguments
+    /// * `target` - Host name to resolve
+    fn resolve(&self, target: &str) -> Result<AddressSet, Error> {
+        let name = Name::from_ascii(target)?;
+        let mut set = AddressSet::default();
+        for query_type in &[RecordType::A, RecordType::AAAA] {
+            let response = self.query(&name, *query_type)?;
+            for answer in response.answers().iter() {
+                if let Some(ip) = answer.rdata().to_ip_addr() {
+                    let asn = self.asn_source.lookup(ip).map(|m| m.asn);
+                    set.insert(&ip.to_string(), asn);
+                }
+            }
+        }
+        Ok(set)
+    }
+
+    /// Query the resolver, retrying up to `QUERY_ATTEMPTS` times should it
+    /// fail, e.g. time out.
+    ///
+    /// # Arguments
+    /// * `name` - Name to query
+    /// * `query_type` - The DNS RecordType to query
+    fn query(&self, name: &Name, query_type: RecordType) -> Result<DnsResponse, Error> {
+        let mut attempt = 1;
+        loop {
+            match self.dns_client.query(name, DNSClass::IN, query_type) {
+                Ok(r) => return Ok(r),
+                Err(e) if attempt < QUERY_ATTEMPTS => {
+                    stall(e.to_string());
+                    attempt += 1;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+fn main() -> Result<(), Error> {
+    let matches = App::new("apex_alias")
+        .version(ALIAS_VERSION)
+        .author(ALIAS_AUTHORS)
+        .about(ALIAS_ABOUT)
+        .arg(
+            Arg::with_name("sqlite-db")
+                .help("Path to SQLite database")
+                .long("sqlite-db")
+                .required(true)
+                .value_name("FILE"),
+        )
+        .arg(
+            Arg::with_name("asn-db")
+                .help("Path to the ASN source, by default a MaxMind GeoLite2 ASN Database")
+                .long("asn-db")
+                .required(true)
+                .value_name("FILE"),
+        )
+        .arg(
+            Arg::with_name("asn-source")
+                .help("Kind of ASN source given by --asn-db")
+                .long("asn-source")
+                .takes_value(true)
+                .possible_values(&["mmdb", "prefix2as", "mrt"])
+                .default_value("mmdb"),
+        )
+        .arg(
+            Arg::with_name("resolver")
+                .help("IP address of DNS resolver to query, including port.")
+                .long("resolver")
+                .takes_value(true)
+                .required(true),
+        )
+        .get_matches();
+
+    let resolver: SocketAddr = matches.value_of("resolver").unwrap().parse()?;
+    let sqlite_db = matches.value_of("sqlite-db").unwrap();
+    let asn_db = matches.value_of("asn-db").unwrap();
+
+    let asn_source = open_source(matches.value_of("asn-source").unwrap(), asn_db)?;
+
+    let apex_alias = ApexAlias::new(resolver, sqlite_db, asn_source)?;
+
+    // --------------------------
+    //     Start of processing
+    // --------------------------
+    let start = start_processing_marker(format!(
+        "Classifying apex of domains using resolver {} into {}",
+        resolver.to_string(),
+        sqlite_db
+    ));
+
+    apex_alias.process_all();
+
+    // --------------------------
+    //       End of processing
+    // --------------------------
+    end_processing_marker("Classification completed", start);
+
+    Ok(())
+}
@@ -0,0 +1,159 @@
+extern crate clap;
+extern crate damp;
+extern crate diesel;
+extern crate failure;
+
+use clap::{App, Arg};
+use damp::as2org;
+use damp::model::as_org::NewAsOrg;
+use damp::model::asn::NewAsn;
+use damp::model::connect;
+use damp::*;
+use diesel::prelude::*;
+use failure::Error;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+static AS_ORG_VERSION: &'static str = env!("CARGO_PKG_VERSION");
+static AS_ORG_AUTHORS: &'static str = env!("CARGO_PKG_AUTHORS");
+static AS_ORG_ABOUT: &'static str = r#"
+Loads the CAIDA AS Organizations dataset into the as_org table, replacing any
+dataset loaded before, then maps every ASN stored in the record table to the
+organisation it is registered to via the org_id column of the asn table. Both
+are done in a single transaction, so a load that fails, e.g. on an ASN that
+cannot be inserted, leaves the previous dataset and mapping in place.
+
+Providers such as Amazon, Google and Akamai operate many sibling ASNs, which
+share an organisation id, so that diversity may be counted by organisation
+rather than by ASN. ASNs absent from the dataset are left with a NULL org_id.
+
+The dataset is the pipe separated as-org2info.txt file, compressed or
+not, available from https://www.caida.org/catalog/datasets/as-organizations/
+"#;
+
+fn main() -> Result<(), Error> {
+    let matches = App::new("as_org_load")
+        .version(AS_ORG_VERSION)
+        .author(AS_ORG_AUTHORS)
+        .about(AS_ORG_ABOUT)
+        .arg(
+            Arg::with_name("sqlite-db")
+                .help("Path to SQLite database")
+                .long("sqlite-db")
+                .required(true)
+                .value_name("FILE"),
+        )
+        .arg(
+            Arg::with_name("as2org")
+                .help("Path to CAIDA AS Organizations dataset")
+                .long("as2org")
+                .required(true)
+                .value_name("FILE"),
+        )
+        .get_matches();
+
+    let sqlite_db = matches.value_of("sqlite-db").unwrap();
+    let as2org_file = matches.value_of("as2org").unwrap();
+    let dataset = Path::new(as2org_file)
+        .file_name()
+        .map(|f| f.to_string_lossy().into_owned())
+        .unwrap_or_else(|| as2org_file.to_string());
+
+    // --------------------------
+    //     Start of processing
+    // --------------------------
+    let start = start_processing_marker(format!(
+        "Loading AS organisations from {} into {}",
+        as2org_file, sqlite_db
+    ));
+
+    let organisations = as2org::from_path(as2org_file)?;
+    let conn = connect(sqlite_db.to_string());
+
+    // The dataset is replaced and the ASNs mapped to it as one, so that an
+    // interrupted load leaves the previous dataset and mapping in place
+    conn.transaction::<_, Error, _>(|| {
+        diesel::delete(schema::as_org::table).execute(&conn)?;
+        for o in &organisations {
+            let row = NewAsOrg {
+                number: &o.asn,
+                aut_name: o.aut_name.as_ref().map(String::as_str),
+                org_id: &o.org_id,
+                org_name: o.org_name.as_ref().map(String::as_str),
+                country: o.country.as_ref().map(String::as_str),
+                source: o.source.as_ref().map(String::as_str),
+                dataset: &dataset,
+            };
+            // Any ASN that cannot be inserted rolls back the whole dataset
+            diesel::insert_into(schema::as_org::table)
+                .values(&row)
+                .execute(&conn)
+                .map_err(|e| failure::format_err!("Unable to insert AS{} - {}", o.asn, e))?;
+        }
+        println!("Loaded {} ASNs", organisations.len());
+
+        // ASNs collected before the asn table existed are added to it first, so
+        // that every ASN in the record table is mapped
+        let collected: Vec<i32> = {
+            use damp::schema::record::dsl::*;
+            record
+                .select(asn)
+                .distinct()
+                .load::<Option<i32>>(&conn)?
+                .into_iter()
+                .filter_map(|a| a)
+                .collect()
+        };
+        for n in &collected {
+            let row = NewAsn {
+                number: n,
+                organisation: None,
+            };
+            diesel::insert_or_ignore_into(schema::asn::table)
+                .values(&row)
+                .execute(&conn)?;
+        }
+
+        let org_ids: HashMap<i32, &str> = organisations
+            .iter()
+            .map(|o| (o.asn, o.org_id.as_str()))
+            .collect();
+        let numbers: Vec<i32> = {
+            use damp::schema::asn::dsl::*;
+            asn.select(number).load::<i32>(&conn)?
+        };
+        let mut mapped: HashSet<&str> = HashSet::new();
+        let mut unmapped = 0;
+        for n in &numbers {
+            use damp::schema::asn::dsl::*;
+            let found = org_ids.get(n).cloned();
+            match found {
+                Some(o) => {
+                    mapped.insert(o);
+                }
+                None => unmapped += 1,
+            }
+            match diesel::update(asn.filter(number.eq(n)))
+                .set(org_id.eq(found))
+                .execute(&conn)
+            {
+                Ok(_) => (),
+                Err(e) => eprintln!("Unable to map AS{} - {}", n, e),
+            }
+        }
+        println!(
+            "Mapped {} of {} ASNs to {} organisations",
+            numbers.len() - unmapped,
+            numbers.len(),
+            mapped.len()
+        );
+        Ok(())
+    })?;
+
+    // --------------------------
+    //       End of processing
+    // --------------------------
+    end_processing_marker("Loading completed", start);
+
+    Ok(())
+}
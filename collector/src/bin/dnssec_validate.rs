@@ -0,0 +1,174 @@
+extern crate clap;
+extern crate damp;
+extern crate diesel;
+extern crate failure;
+
+use clap::{App, Arg};
+use damp::dnssec::{dnssec_type, name_key, zone_chain, RecordStore, Status, Validation, Validator};
+use damp::model::connect;
+use damp::model::dnssec::{DnssecRecord, NewDnssecStatus};
+use damp::model::domain::Domain;
+use damp::schema;
+use damp::{end_processing_marker, start_processing_marker};
+use diesel::prelude::*;
+use failure::Error;
+use trust_dns::rr::{Name, Record, RecordType};
+use trust_dns_proto::rr::dnssec::rdata::DNSSECRecordType;
+use trust_dns_proto::serialize::binary::BinDecodable;
+
+static VALIDATE_VERSION: &'static str = env!("CARGO_PKG_VERSION");
+static VALIDATE_AUTHORS: &'static str = env!("CARGO_PKG_AUTHORS");
+static VALIDATE_ABOUT: &'static str = r#"
+Validates the DNSSEC chain of trust of each domain's apex, using only the
+records previously collected by the domain_query(8) binary with --dnssec.
+
+Starting from the trust anchor, each DS and DNSKEY record set along the chain
+of zones down to the apex must be correctly signed, and finally the apex's SOA.
+Each domain is given a status of:
+* secure        - the chain of trust is unbroken
+* insecure      - a zone along the chain is proven to be unsigned
+* bogus         - a signature, digest or proof of non-existence failed
+* indeterminate - not enough records were collected to decide, or some of
+                  those collected could not be parsed
+
+The status given by an earlier run is replaced.
+
+The trust anchor file holds DS records in presentation format, one per line:
+
+. IN DS 20326 8 2 E06D44B80B8F1D39A95C0B0D7C65D08458E880409BBC683457104237C7F8EC8D
+"#;
+
+fn main() -> Result<(), Error> {
+    let matches = App::new("dnssec_validate")
+        .version(VALIDATE_VERSION)
+        .author(VALIDATE_AUTHORS)
+        .about(VALIDATE_ABOUT)
+        .arg(
+            Arg::with_name("sqlite-db")
+                .help("Path to SQLite database")
+                .long("sqlite-db")
+                .required(true)
+                .value_name("FILE"),
+        )
+        .arg(
+            Arg::with_name("trust-anchor")
+                .help("Path to file of trust anchor DS records")
+                .long("trust-anchor")
+                .required(true)
+                .value_name("FILE"),
+        )
+        .get_matches();
+
+    let sqlite_db = matches.value_of("sqlite-db").unwrap();
+    let anchor_path = matches.value_of("trust-anchor").unwrap();
+
+    // --------------------------
+    //     Start of processing
+    // --------------------------
+    let start = start_processing_marker(format!(
+        "Validating DNSSEC of domains in {} from trust anchor {}",
+        sqlite_db, anchor_path
+    ));
+
+    let validator = Validator::from_path(anchor_path)?;
+    let conn = connect(sqlite_db.to_string());
+    let domains = schema::domain::table.load::<Domain>(&conn)?;
+
+    for d in &domains {
+        let apex = match Name::from_ascii(&d.fqdn) {
+            Ok(n) => n,
+            Err(e) => {
+                eprintln!("Error parsing domain {} - {}", d.fqdn, e);
+                continue;
+            }
+        };
+        let (store, time, corrupt) = load_store(&apex, &conn)?;
+        let validation = if corrupt > 0 {
+            Validation {
+                status: Status::Indeterminate,
+                reason: format!("{} collected records could not be parsed", corrupt),
+            }
+        } else {
+            validator.validate(&store, &apex, time as u32)
+        };
+
+        let status = NewDnssecStatus {
+            domain: &d.rowid,
+            status: validation.status.as_str(),
+            reason: &validation.reason,
+            validation_time: &time,
+        };
+        match diesel::replace_into(schema::dnssec_status::table)
+            .values(&status)
+            .execute(&conn)
+        {
+            Ok(_) => (),
+            Err(e) => eprintln!("Error inserting DNSSEC status for {} - {}", d.fqdn, e),
+        };
+    }
+
+    // --------------------------
+    //       End of processing
+    // --------------------------
+    end_processing_marker("Validation completed", start);
+
+    Ok(())
+}
+
+/// Load the records collected for every name along the chain from the root to
+/// the apex, returning them along with the latest time they were collected and
+/// the count of rows that could not be parsed, each of which is reported and
+/// skipped.
+///
+/// # Arguments
+/// * `apex` - Apex of the zone to be validated
+/// * `conn` - SQLite connection
+fn load_store(apex: &Name, conn: &SqliteConnection) -> Result<(RecordStore, i64, usize), Error> {
+    use damp::schema::dnssec_record::dsl::*;
+
+    let names: Vec<String> = zone_chain(apex).iter().map(name_key).collect();
+    let rows = dnssec_record
+        .filter(query_name.eq_any(names))
+        .load::<DnssecRecord>(conn)?;
+
+    let mut store = RecordStore::new();
+    let mut latest: i64 = 0;
+    let mut corrupt: usize = 0;
+    for row in &rows {
+        let name = match Name::from_ascii(&row.query_name) {
+            Ok(n) => n,
+            Err(e) => {
+                eprintln!("Skipping dnssec_record {} - {}", row.rowid, e);
+                corrupt += 1;
+                continue;
+            }
+        };
+        let rtype = match parse_query_type(&row.query_type) {
+            Some(t) => t,
+            None => continue,
+        };
+        latest = latest.max(row.query_time);
+        match &row.data {
+            Some(bytes) => match Record::from_bytes(bytes) {
+                Ok(record) => store.insert(&name, rtype, record),
+                Err(e) => {
+                    eprintln!("Skipping dnssec_record {} - {}", row.rowid, e);
+                    corrupt += 1;
+                }
+            },
+            None => store.mark_queried(&name, rtype),
+        }
+    }
+    Ok((store, latest, corrupt))
+}
+
+/// The query types collected by domain_query(8), the DNSSEC types of which
+/// `RecordType::from_str` does not recognise.
+fn parse_query_type(query_type: &str) -> Option<RecordType> {
+    match query_type {
+        "DNSKEY" => Some(dnssec_type(DNSSECRecordType::DNSKEY)),
+        "DS" => Some(dnssec_type(DNSSECRecordType::DS)),
+        "SOA" => Some(RecordType::SOA),
+        _ => None,
+    }
+}
@@ -0,0 +1,212 @@
+extern crate clap;
+extern crate damp;
+extern crate diesel;
+extern crate failure;
+
+use clap::{App, Arg};
+use damp::fingerprint::{hosted_by, Fingerprints};
+use damp::model::connect;
+use damp::model::domain::Domain;
+use damp::model::hosting::NewHosting;
+use damp::model::record::Record;
+use damp::schema;
+use damp::{end_processing_marker, start_processing_marker};
+use diesel::prelude::*;
+use failure::Error;
+use std::collections::{HashMap, HashSet};
+
+static HOSTING_VERSION: &'static str = env!("CARGO_PKG_VERSION");
+static HOSTING_AUTHORS: &'static str = env!("CARGO_PKG_AUTHORS");
+static HOSTING_ABOUT: &'static str = r#"
+Assigns a "website hosted by" label to each domain in a SQLite database already
+populated by the domain_query(8) binary.
+
+Every CNAME record is matched against a fingerprint database of target suffixes
+belonging to hosting and CDN providers (e.g. *.cloudfront.net), and the ASN of
+every A and AAAA answer is matched against the providers' networks. The two are
+combined into a label with a confidence of:
+* high   - the CNAME target and ASN agree
+* medium - only the CNAME target matched
+* low    - only the ASN matched
+
+The www name is preferred for the label, falling back to the apex if nothing
+matched for www. The label of a domain classified by an earlier run is
+replaced, and removed where nothing matches any more.
+"#;
+
+fn main() -> Result<(), Error> {
+    let matches = App::new("domain_hosting")
+        .version(HOSTING_VERSION)
+        .author(HOSTING_AUTHORS)
+        .about(HOSTING_ABOUT)
+        .arg(
+            Arg::with_name("sqlite-db")
+                .help("Path to SQLite database")
+                .long("sqlite-db")
+                .required(true)
+                .value_name("FILE"),
+        )
+        .arg(
+            Arg::with_name("fingerprints")
+                .help("Path to the hosting fingerprint CSV")
+                .long("fingerprints")
+                .required(true)
+                .value_name("FILE"),
+        )
+        .get_matches();
+
+    let sqlite_db = matches.value_of("sqlite-db").unwrap();
+    let fingerprint_path = matches.value_of("fingerprints").unwrap();
+
+    // --------------------------
+    //     Start of processing
+    // --------------------------
+    let start = start_processing_marker(format!(
+        "Classifying hosting of domains in {} using {}",
+        sqlite_db, fingerprint_path
+    ));
+
+    let fingerprints = Fingerprints::from_path(fingerprint_path)?;
+    let conn = connect(sqlite_db.to_string());
+    let domains = schema::domain::table.load::<Domain>(&conn)?;
+
+    for d in &domains {
+        let records = schema::record::table
+            .filter(schema::record::domain.eq(d.rowid))
+            .order(schema::record::rowid.asc())
+            .load::<Record>(&conn)?;
+
+        tag_cname_records(&records, &fingerprints, &conn)?;
+
+        let label = [true, false]
+            .iter()
+            .filter_map(|is_www| classify(&records, *is_www, &fingerprints).map(|l| (*is_www, l)))
+            .next();
+
+        if let Some((is_www, (cname_provider, asn_provider))) = label {
+            let cname_provider = cname_provider.as_ref().map(String::as_str);
+            let asn_provider = asn_provider.as_ref().map(String::as_str);
+            let (provider, confidence) = hosted_by(cname_provider, asn_provider).unwrap();
+            let hosting = NewHosting {
+                domain: &d.rowid,
+                is_www: &is_www,
+                provider: &provider,
+                confidence: confidence.as_str(),
+                cname_provider,
+                asn_provider,
+            };
+            match diesel::replace_into(schema::hosting::table)
+                .values(&hosting)
+                .execute(&conn)
+            {
+                Ok(_) => (),
+                Err(e) => eprintln!("Error inserting hosting for {} - {}", d.fqdn, e),
+            };
+        } else {
+            diesel::delete(schema::hosting::table.filter(schema::hosting::domain.eq(d.rowid)))
+                .execute(&conn)?;
+        }
+    }
+
+    // --------------------------
+    //       End of processing
+    // --------------------------
+    end_processing_marker("Classification completed", start);
+
+    Ok(())
+}
+
+/// Persist the provider of every CNAME record whose target matches a
+/// fingerprint.
+///
+/// # Arguments
+/// * `records` - All records belonging to a domain
+/// * `fingerprints` - The fingerprint database
+/// * `conn` - SQLite connection
+fn tag_cname_records(
+    records: &[Record],
+    fingerprints: &Fingerprints,
+    conn: &SqliteConnection,
+) -> QueryResult<()> {
+    use damp::schema::record::dsl::*;
+
+    for r in records
+        .iter()
+        .filter(|r| r.record_type.as_ref().map(String::as_str) == Some("CNAME"))
+    {
+        let matched = r
+            .address
+            .as_ref()
+            .and_then(|a| fingerprints.match_cname(a));
+        if let Some(p) = matched {
+            diesel::update(record.filter(rowid.eq(r.rowid)))
+                .set(provider.eq(p))
+                .execute(conn)?;
+        }
+    }
+    Ok(())
+}
+
+/// Determine the providers matched by the CNAME targets and the ASNs of the
+/// answers for either the apex or www. Where a CNAME chain is present, the
+/// last matching target in the chain is taken as it is closest to the host
+/// serving the content. Where addresses span several providers' ASNs, the
+/// most common is taken. Only the answers made without an EDNS Client Subnet
+/// to the resolver of the first of them are considered, so that each answer
+/// is counted once.
+///
+/// # Arguments
+/// * `records` - All records belonging to a domain, in the order inserted
+/// * `is_www` - Whether to consider the answers for www or the apex
+/// * `fingerprints` - The fingerprint database
+fn classify(
+    records: &[Record],
+    is_www: bool,
+    fingerprints: &Fingerprints,
+) -> Option<(Option<String>, Option<String>)> {
+    // Addresses of name servers are children of NS records, which we exclude
+    let ns_rows: HashSet<i64> = records
+        .iter()
+        .filter(|r| r.record_type.as_ref().map(String::as_str) == Some("NS"))
+        .map(|r| r.rowid)
+        .collect();
+    let queried: Vec<&Record> = records
+        .iter()
+        .filter(|r| r.is_www == is_www && r.client_subnet.is_none())
+        .collect();
+    let resolver = &queried.first()?.resolver;
+    let answers: Vec<&Record> = queried
+        .iter()
+        .cloned()
+        .filter(|r| r.resolver == *resolver)
+        .filter(|r| r.parent.map_or(true, |p| !ns_rows.contains(&p)))
+        .filter(|r| match r.record_type.as_ref().map(String::as_str) {
+            Some("CNAME") | Some("A") | Some("AAAA") => true,
+            _ => false,
+        })
+        .collect();
+
+    let cname_provider = answers
+        .iter()
+        .filter(|r| r.record_type.as_ref().map(String::as_str) == Some("CNAME"))
+        .filter_map(|r| r.address.as_ref().and_then(|a| fingerprints.match_cname(a)))
+        .last()
+        .map(str::to_string);
+
+    let mut asn_counts: HashMap<&str, usize> = HashMap::new();
+    for provider in answers
+        .iter()
+        .filter_map(|r| r.asn.and_then(|a| fingerprints.match_asn(a)))
+    {
+        *asn_counts.entry(provider).or_insert(0) += 1;
+    }
+    let asn_provider = asn_counts
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(a.0)))
+        .map(|(p, _)| p.to_string());
+
+    match (&cname_provider, &asn_provider) {
+        (None, None) => None,
+        _ => Some((cname_provider, asn_provider)),
+    }
+}
@@ -7,16 +7,25 @@ extern crate publicsuffix;
 
 extern crate damp;
 
+use chrono::{DateTime, Utc};
 use clap::{App, Arg};
+use damp::compress;
 use damp::dns::{get_root_domain, get_sub_domain};
-use damp::model::connect;
 use damp::model::domain::NewDomain;
+use damp::model::list::{NewList, NewListMember};
+use damp::model::rejected_domain::NewRejectedDomain;
+use damp::model::{connect, last_insert_rowid};
+use damp::ranking::{bucket_from_path, file_hash, tranco_list_id, ListFormat, ListReader};
 use damp::schema;
-use damp::{end_processing_marker, start_processing_marker};
+use damp::{end_processing_marker, start_processing_marker, unix_time};
 use diesel::prelude::*;
+use diesel::query_builder::{AstPass, QueryFragment, QueryId};
+use diesel::sql_types::{BigInt, Integer, Nullable, Text};
+use diesel::sqlite::Sqlite;
 use failure::Error;
 use publicsuffix::{Domain, List};
-use std::str::FromStr;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
 
 static LOADER_VERSION: &'static str = env!("CARGO_PKG_VERSION");
 static LOADER_AUTHORS: &'static str = env!("CARGO_PKG_AUTHORS");
@@ -24,12 +33,46 @@ static LOADER_ABOUT: &'static str = r#"
 Loader takes the domain ranking CSV, performs analysis on the
 domain to determine name, TLD etc and persists in the SQLite table.
 
-The spreadsheet must be formatted with values of ranking and domain,
-containing no column header. This is a common format used by many
+By default the spreadsheet must be formatted with values of ranking and
+domain, containing no column header. This is a common format used by many
 lists such as Alexa, Cisco Umbrella:
 
 1,example.com
 2,example.net
+
+Other lists are read with --format:
+* tranco   - Tranco, its list ID taken from a file name of tranco_<ID>
+* majestic - Majestic Million, by its GlobalRank and Domain columns
+* umbrella - Cisco Umbrella
+* alexa    - Alexa
+* radar    - Cloudflare Radar, each domain ranked as the size of the bucket
+             given by --bucket or the top-<N> of the file name
+* crux     - Chrome UX Report origin and rank, each origin's host ranked as
+             the size of its bucket
+
+Several lists may be loaded into the same database, each recorded in the list
+table along with its provider, list ID, download date and SHA-256. A domain
+already loaded from another list is not added again, only its membership and
+rank in the new list, so that each domain is queried only once. Loading a
+list of the same name again updates the rank of its domains rather than
+adding them twice, reporting how many were inserted, updated or unchanged,
+and removes from the list those domains it no longer lists.
+
+Domains are inserted in batches of --batch-size, each in its own transaction,
+reporting progress as each is committed. A load that is aborted keeps only
+the batches committed before it, never part of one. An earlier load of the
+list is replaced in a single transaction once the load completes - its
+details, rejected lines and the domains no longer listed - so an aborted
+reload leaves those of the earlier load.
+
+Lines that cannot be loaded, as they are not UTF-8, their rank is not a
+number, their domain cannot be parsed against the Public Suffix List or it
+cannot be inserted, are recorded in the rejected_domain table with the reason,
+and summarised once the load completes.
+
+The list and the public suffix list may be compressed, by gzip (.gz), xz
+(.xz) or zstd (.zst), or be the first CSV of a zip archive (.zip) such as
+top-1m.csv.zip, and are read as they are without being extracted.
 "#;
 
 fn main() -> Result<(), Error> {
@@ -51,6 +94,56 @@ fn main() -> Result<(), Error> {
                 .required(true)
                 .value_name("FILE"),
         )
+        .arg(
+            Arg::with_name("format")
+                .help("Format of the ranking list")
+                .long("format")
+                .takes_value(true)
+                .possible_values(&[
+                    "csv", "tranco", "majestic", "umbrella", "alexa", "radar", "crux",
+                ])
+                .default_value("csv"),
+        )
+        .arg(
+            Arg::with_name("bucket")
+                .help("Rank of every domain of a Cloudflare Radar bucket, e.g. 1000")
+                .long("bucket")
+                .takes_value(true)
+                .value_name("SIZE"),
+        )
+        .arg(
+            Arg::with_name("list-name")
+                .help("Name of the list, by default its file name")
+                .long("list-name")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("provider")
+                .help("Organisation publishing the list, by default that of the format")
+                .long("provider")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("list-id")
+                .help("Identifier of the list, e.g. the Tranco list ID")
+                .long("list-id")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("download-date")
+                .help("Date the list was downloaded, by default the date the file was modified")
+                .long("download-date")
+                .takes_value(true)
+                .value_name("YYYY-MM-DD"),
+        )
+        .arg(
+            Arg::with_name("batch-size")
+                .help("Number of domains inserted per transaction")
+                .long("batch-size")
+                .takes_value(true)
+                .value_name("SIZE")
+                .default_value("10000"),
+        )
         .arg(
             Arg::with_name("public-suffix-db")
                 .help("Path to the Public Suffix List")
@@ -63,34 +156,167 @@ fn main() -> Result<(), Error> {
     let csv_file = matches.value_of("csv").unwrap();
     let suffix_path = matches.value_of("public-suffix-db").unwrap();
     let sqlite_db = matches.value_of("sqlite-db").unwrap();
+    let format: ListFormat = matches.value_of("format").unwrap().parse()?;
+    let bucket: Option<i32> = match matches.value_of("bucket") {
+        Some(b) => Some(b.parse()?),
+        None => bucket_from_path(csv_file),
+    };
+    let batch_size: usize = matches.value_of("batch-size").unwrap().parse()?;
+    if batch_size == 0 {
+        return Err(failure::format_err!("Batch size must be at least 1"));
+    }
+    let list_name = match matches.value_of("list-name") {
+        Some(n) => n.to_string(),
+        None => Path::new(csv_file)
+            .file_name()
+            .map(|f| f.to_string_lossy().into_owned())
+            .unwrap_or_else(|| csv_file.to_string()),
+    };
+    let provider = matches.value_of("provider").or_else(|| format.provider());
+    let list_id = match matches.value_of("list-id") {
+        Some(i) => Some(i.to_string()),
+        None if format == ListFormat::Tranco => tranco_list_id(csv_file),
+        None => None,
+    };
+    let download_date = match matches.value_of("download-date") {
+        Some(d) => d.to_string(),
+        None => DateTime::<Utc>::from(std::fs::metadata(csv_file)?.modified()?)
+            .format("%Y-%m-%d")
+            .to_string(),
+    };
 
     // --------------------------
     //     Start of processing
     // --------------------------
     let start = start_processing_marker(format!(
-        "Loading of domains from {} into {}",
-        csv_file, sqlite_db
+        "Loading of {} domains from {} into {}",
+        format.as_str(),
+        csv_file,
+        sqlite_db
     ));
 
-    let list = List::from_path(suffix_path.to_string()).unwrap();
+    let list = List::from_reader(compress::open(suffix_path)?).unwrap();
     let conn = connect(sqlite_db.to_string());
-    let mut csv_reader = csv::ReaderBuilder::new()
-        .has_headers(false)
-        .from_path(csv_file)?;
-
-    for line in csv_reader.records() {
-        // For record.get(), offset 0 is rank, 1 is domain
-        let record = line?;
-        let raw_domain = record.get(1);
-        let rank = FromStr::from_str(record.get(0).unwrap()).unwrap_or(0);
-        match list.parse_domain(raw_domain.unwrap()) {
-            Ok(d) => {
-                match insert_domain(&rank, &d, &conn) {
-                    Ok(_) => (),
-                    Err(e) => eprintln!("Error inserting domain - {}", e),
-                };
+
+    let new_list = NewList {
+        name: &list_name,
+        provider,
+        list_id: list_id.as_ref().map(String::as_str),
+        format: format.as_str(),
+        download_date: &download_date,
+        file_hash: &file_hash(csv_file)?,
+        load_time: &unix_time(),
+    };
+    let existing_list = schema::list::table
+        .filter(schema::list::name.eq(&list_name))
+        .select(schema::list::rowid)
+        .first::<i64>(&conn)
+        .optional()?;
+    // The details of a list being reloaded are updated only once the load
+    // completes, so an aborted reload leaves those of the earlier load
+    let list_rowid = match existing_list {
+        Some(r) => r,
+        None => {
+            diesel::insert_into(schema::list::table)
+                .values(&new_list)
+                .execute(&conn)?;
+            diesel::select(last_insert_rowid).get_result::<i64>(&conn)?
+        }
+    };
+    println!(
+        "{} list {} ({}) from {}",
+        match existing_list {
+            Some(_) => "Reloading",
+            None => "Loading",
+        },
+        list_name,
+        list_id.as_ref().map_or("no list ID", String::as_str),
+        download_date
+    );
+
+    let mut batch: Vec<PendingDomain> = Vec::with_capacity(batch_size);
+    let mut seen: HashSet<i64> = HashSet::new();
+    let mut counts = LoadCounts::default();
+    let mut rejections = Rejections::new(list_rowid);
+    let mut last_line = 0;
+    let file = compress::open_member(csv_file, ".csv")?;
+    for line in ListReader::new(format, file, bucket)? {
+        let ranked = line?;
+        last_line = ranked.line;
+        if let Some(e) = ranked.invalid {
+            rejections.reject(ranked.line, &ranked.raw, "invalid_line", Some(e));
+            continue;
+        }
+        let rank = match ranked.rank {
+            Some(r) => r,
+            None => {
+                rejections.reject(ranked.line, &ranked.raw, "invalid_rank", None);
+                continue;
             }
-            Err(e) => eprintln!("Error parsing domain - {}", e),
+        };
+        match list.parse_domain(&ranked.domain) {
+            Ok(d) => batch.push(PendingDomain::new(rank, &d, ranked.line, ranked.raw)),
+            Err(e) => rejections.reject(
+                ranked.line,
+                &ranked.raw,
+                "invalid_domain",
+                Some(e.to_string()),
+            ),
+        }
+        if batch.len() >= batch_size {
+            load_batch(
+                list_rowid,
+                &batch,
+                &mut seen,
+                &mut counts,
+                &mut rejections,
+                &conn,
+            )?;
+            batch.clear();
+            println!("Loaded {} domains to line {}", counts.total(), last_line);
+        }
+    }
+    if !batch.is_empty() {
+        load_batch(
+            list_rowid,
+            &batch,
+            &mut seen,
+            &mut counts,
+            &mut rejections,
+            &conn,
+        )?;
+        println!("Loaded {} domains to line {}", counts.total(), last_line);
+    }
+
+    // An earlier load of the list is replaced within a single transaction once
+    // this one completes: its details and rejected lines are replaced, the
+    // domains it no longer lists removed, and the rank of a domain, its rank in
+    // the first list it was loaded from, brought up to date
+    let removed = conn.transaction::<_, Error, _>(|| {
+        let removed = match existing_list {
+            Some(_) => replace_earlier_load(list_rowid, &new_list, &seen, &conn)?,
+            None => 0,
+        };
+        rejections.save(&conn)?;
+        Ok(removed)
+    })?;
+    println!(
+        "{} domains inserted, {} updated and {} unchanged",
+        counts.inserted, counts.updated, counts.unchanged
+    );
+    if removed > 0 {
+        println!("{} domains no longer listed removed from the list", removed);
+    }
+    if counts.duplicate > 0 {
+        println!("{} domains listed more than once", counts.duplicate);
+    }
+    if rejections.total() > 0 {
+        println!(
+            "{} lines rejected, recorded in the rejected_domain table:",
+            rejections.total()
+        );
+        for (reason, count) in &rejections.counts {
+            println!("  {} - {}", reason, count);
         }
     }
 
@@ -102,25 +328,390 @@ fn main() -> Result<(), Error> {
     Ok(())
 }
 
-/// Insert a domain from the spreadsheet into the SQLite database.
+/// A domain read from the spreadsheet, parsed and awaiting insertion.
+struct PendingDomain {
+    rank: i32,
+    fqdn: String,
+    sub: Option<String>,
+    root: Option<String>,
+    suffix: Option<String>,
+    /// Line of the spreadsheet, kept should the domain be rejected
+    line: u64,
+    raw: String,
+}
+
+impl PendingDomain {
+    fn new(rank: i32, domain: &Domain, line: u64, raw: String) -> PendingDomain {
+        PendingDomain {
+            rank,
+            fqdn: domain.to_string(),
+            sub: get_sub_domain(domain),
+            root: get_root_domain(domain),
+            suffix: domain.suffix().map(String::from),
+            line,
+            raw,
+        }
+    }
+}
+
+/// The lines of the spreadsheet rejected, counted by reason and held until
+/// the load completes to be recorded in the rejected_domain table.
+struct Rejections {
+    list: i64,
+    lines: Vec<(u64, String, &'static str, Option<String>)>,
+    counts: BTreeMap<&'static str, usize>,
+}
+
+impl Rejections {
+    fn new(list: i64) -> Rejections {
+        Rejections {
+            list,
+            lines: Vec::new(),
+            counts: BTreeMap::new(),
+        }
+    }
+
+    /// Record a line of the spreadsheet as rejected.
+    ///
+    /// # Arguments
+    /// * `line` - Line of the spreadsheet
+    /// * `raw` - The line as read
+    /// * `reason` - Reason the line was rejected, e.g. "invalid_rank"
+    /// * `error` - The error raised, where there was one
+    fn reject(&mut self, line: u64, raw: &str, reason: &'static str, error: Option<String>) {
+        self.lines.push((line, raw.to_string(), reason, error));
+        *self.counts.entry(reason).or_insert(0) += 1;
+    }
+
+    /// Persist the lines rejected to the rejected_domain table.
+    ///
+    /// # Arguments
+    /// * `conn` - SQLite connection
+    fn save(&self, conn: &SqliteConnection) -> QueryResult<()> {
+        for (line, raw, reason, error) in &self.lines {
+            let row = NewRejectedDomain {
+                list: &self.list,
+                line: &(*line as i64),
+                raw,
+                reason,
+                error: error.as_ref().map(String::as_str),
+            };
+            diesel::insert_into(schema::rejected_domain::table)
+                .values(&row)
+                .execute(conn)?;
+        }
+        Ok(())
+    }
+
+    fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+}
+
+/// Number of domains of the list inserted, updated or unchanged by a load.
+#[derive(Default)]
+struct LoadCounts {
+    /// Domains new to the list
+    inserted: usize,
+    /// Domains already in the list whose rank has changed
+    updated: usize,
+    /// Domains already in the list at the same rank
+    unchanged: usize,
+    /// Domains listed again further down the list, only the first being kept
+    duplicate: usize,
+}
+
+impl LoadCounts {
+    fn total(&self) -> usize {
+        self.inserted + self.updated + self.unchanged
+    }
+
+    fn add(&mut self, other: &LoadCounts) {
+        self.inserted += other.inserted;
+        self.updated += other.updated;
+        self.unchanged += other.unchanged;
+        self.duplicate += other.duplicate;
+    }
+}
+
+/// Load a batch of domains as `insert_batch` does. Where the batch cannot be
+/// inserted, each of its domains is loaded alone, so that only those domains
+/// which cannot be inserted are rejected.
+///
+/// # Arguments
+/// * `list` - Row ID of the list
+/// * `batch` - Parsed domains and their rankings
+/// * `seen` - Row IDs of the domains loaded so far from the list
+/// * `counts` - Counts of the domains loaded so far from the list
+/// * `rejections` - The lines rejected so far
+/// * `conn` - SQLite connection
+fn load_batch(
+    list: i64,
+    batch: &[PendingDomain],
+    seen: &mut HashSet<i64>,
+    counts: &mut LoadCounts,
+    rejections: &mut Rejections,
+    conn: &SqliteConnection,
+) -> Result<(), Error> {
+    match insert_batch(list, batch, seen, conn) {
+        Ok((loaded_counts, loaded)) => {
+            counts.add(&loaded_counts);
+            seen.extend(loaded);
+        }
+        Err(_) if batch.len() > 1 => {
+            for single in batch.chunks(1) {
+                load_batch(list, single, seen, counts, rejections, conn)?;
+            }
+        }
+        Err(e) => {
+            let d = &batch[0];
+            rejections.reject(d.line, &d.raw, "insert_failed", Some(e.to_string()));
+        }
+    }
+    Ok(())
+}
+
+/// Insert a batch of domains from the spreadsheet into the SQLite database
+/// within a single transaction, such that an aborted load leaves none of the
+/// batch behind. New domains and list members are each inserted with
+/// multi-row INSERT statements, as `InsertDomains` and `InsertMembers`. Domains already loaded, from this list or another, are not
+/// added again, only their rank in the list, which is updated where the list
+/// is being reloaded. Returns the counts of the batch and the row IDs of the
+/// domains it loaded.
 ///
 /// # Arguments
-/// * `rank` - Domain ranking
-/// * `domain` - Parsed Domain
+/// * `list` - Row ID of the list
+/// * `batch` - Parsed domains and their rankings
+/// * `seen` - Row IDs of the domains loaded so far from the list
 /// * `conn` - SQLite connection
-fn insert_domain(rank: &i32, domain: &Domain, conn: &SqliteConnection) -> QueryResult<usize> {
-    let sub_domain = get_sub_domain(&domain);
-    let root_domain = get_root_domain(&domain);
-
-    let d = NewDomain {
-        rank: &rank,
-        fqdn: &domain.to_string(),
-        sub: sub_domain.as_ref().map(|x| &**x),
-        root: root_domain.as_ref().map(|x| &**x),
-        suffix: domain.suffix(),
-    };
+fn insert_batch(
+    list: i64,
+    batch: &[PendingDomain],
+    seen: &HashSet<i64>,
+    conn: &SqliteConnection,
+) -> Result<(LoadCounts, HashSet<i64>), Error> {
+    conn.transaction::<_, Error, _>(|| {
+        let mut counts = LoadCounts::default();
+        let mut loaded: HashSet<i64> = HashSet::new();
+        let names: Vec<&str> = batch.iter().map(|d| d.fqdn.as_str()).collect();
+        let mut rowids = domain_rowids(&names, conn)?;
+
+        let mut added: HashSet<&str> = HashSet::new();
+        let new_domains: Vec<NewDomain> = batch
+            .iter()
+            .filter(|d| !rowids.contains_key(&d.fqdn) && added.insert(&d.fqdn))
+            .map(|d| NewDomain {
+                rank: &d.rank,
+                fqdn: &d.fqdn,
+                sub: d.sub.as_ref().map(String::as_str),
+                root: d.root.as_ref().map(String::as_str),
+                suffix: d.suffix.as_ref().map(String::as_str),
+            })
+            .collect();
+        if !new_domains.is_empty() {
+            for chunk in new_domains.chunks(199) {
+                InsertDomains(chunk).execute(conn)?;
+            }
+            let new_names: Vec<&str> = new_domains.iter().map(|d| d.fqdn).collect();
+            rowids.extend(domain_rowids(&new_names, conn)?);
+        }
+
+        let domains: Vec<i64> = rowids.values().cloned().collect();
+        let ranks = member_ranks(list, &domains, conn)?;
+        let mut members: Vec<NewListMember> = Vec::new();
+        for d in batch {
+            let domain_rowid = match rowids.get(&d.fqdn) {
+                Some(r) => r,
+                None => continue,
+            };
+
+            // A domain may appear more than once in a list, e.g. both the
+            // http and https origins of the Chrome UX Report, so is ranked
+            // by its first appearance
+            if seen.contains(domain_rowid) || !loaded.insert(*domain_rowid) {
+                counts.duplicate += 1;
+                continue;
+            }
+            match ranks.get(domain_rowid) {
+                None => {
+                    members.push(NewListMember {
+                        list: &list,
+                        domain: domain_rowid,
+                        rank: &d.rank,
+                    });
+                    counts.inserted += 1;
+                }
+                Some(r) if *r == d.rank => counts.unchanged += 1,
+                Some(_) => {
+                    diesel::update(
+                        schema::list_member::table
+                            .filter(schema::list_member::list.eq(list))
+                            .filter(schema::list_member::domain.eq(domain_rowid)),
+                    )
+                    .set(schema::list_member::rank.eq(d.rank))
+                    .execute(conn)?;
+                    counts.updated += 1;
+                }
+            }
+        }
+        for chunk in members.chunks(333) {
+            InsertMembers(chunk).execute(conn)?;
+        }
+        Ok((counts, loaded))
+    })
+}
+
+/// Replace an earlier load of the list by this one: update its details,
+/// delete the lines it rejected, remove those of its members not loaded by
+/// this load and bring the rank of its domains up to date. Returns the number
+/// of members removed, which are deleted in chunks, as for `domain_rowids`.
+///
+/// # Arguments
+/// * `list` - Row ID of the list
+/// * `new_list` - Details of this load of the list
+/// * `seen` - Row IDs of the domains loaded from the list
+/// * `conn` - SQLite connection
+fn replace_earlier_load(
+    list: i64,
+    new_list: &NewList,
+    seen: &HashSet<i64>,
+    conn: &SqliteConnection,
+) -> QueryResult<usize> {
+    diesel::update(schema::list::table.find(list))
+        .set(new_list)
+        .execute(conn)?;
+    diesel::delete(schema::rejected_domain::table.filter(schema::rejected_domain::list.eq(list)))
+        .execute(conn)?;
+
+    let unlisted: Vec<i64> = schema::list_member::table
+        .filter(schema::list_member::list.eq(list))
+        .select(schema::list_member::domain)
+        .load::<i64>(conn)?
+        .into_iter()
+        .filter(|d| !seen.contains(d))
+        .collect();
+    for chunk in unlisted.chunks(500) {
+        diesel::delete(
+            schema::list_member::table
+                .filter(schema::list_member::list.eq(list))
+                .filter(schema::list_member::domain.eq_any(chunk.to_vec())),
+        )
+        .execute(conn)?;
+    }
+
+    // The rank of a domain is its rank in the first list it was loaded from
+    diesel::sql_query(
+        "UPDATE domain SET rank = (SELECT m.rank FROM list_member m \
+         WHERE m.list = ? AND m.domain = domain.rowid) \
+         WHERE rowid IN (SELECT domain FROM list_member WHERE list = ?) \
+         AND NOT EXISTS (SELECT 1 FROM list_member m \
+         WHERE m.domain = domain.rowid AND m.list < ?)",
+    )
+    .bind::<BigInt, _>(list)
+    .bind::<BigInt, _>(list)
+    .bind::<BigInt, _>(list)
+    .execute(conn)?;
+    Ok(unlisted.len())
+}
+
+/// A single INSERT of many domains, where diesel inserts a slice of them on
+/// SQLite one statement at a time. Each domain binds five parameters, so at
+/// most 199 fit within SQLite's limit of 999.
+struct InsertDomains<'a>(&'a [NewDomain<'a>]);
+
+impl<'a> QueryFragment<Sqlite> for InsertDomains<'a> {
+    fn walk_ast(&self, mut out: AstPass<Sqlite>) -> QueryResult<()> {
+        out.push_sql("INSERT INTO domain (rank, fqdn, sub, root, suffix) VALUES ");
+        for (i, d) in self.0.iter().enumerate() {
+            out.push_sql(if i == 0 { "(" } else { ", (" });
+            out.push_bind_param::<Integer, _>(d.rank)?;
+            out.push_sql(", ");
+            out.push_bind_param::<Text, _>(&d.fqdn)?;
+            out.push_sql(", ");
+            out.push_bind_param::<Nullable<Text>, _>(&d.sub)?;
+            out.push_sql(", ");
+            out.push_bind_param::<Nullable<Text>, _>(&d.root)?;
+            out.push_sql(", ");
+            out.push_bind_param::<Nullable<Text>, _>(&d.suffix)?;
+            out.push_sql(")");
+        }
+        Ok(())
+    }
+}
+
+impl<'a> QueryId for InsertDomains<'a> {
+    type QueryId = ();
+    const HAS_STATIC_QUERY_ID: bool = false;
+}
+
+impl<'a> RunQueryDsl<SqliteConnection> for InsertDomains<'a> {}
+
+/// A single INSERT of many list members, as for `InsertDomains`. Each member
+/// binds three parameters, so at most 333 fit in a statement.
+struct InsertMembers<'a>(&'a [NewListMember<'a>]);
+
+impl<'a> QueryFragment<Sqlite> for InsertMembers<'a> {
+    fn walk_ast(&self, mut out: AstPass<Sqlite>) -> QueryResult<()> {
+        out.push_sql("INSERT INTO list_member (list, domain, rank) VALUES ");
+        for (i, m) in self.0.iter().enumerate() {
+            out.push_sql(if i == 0 { "(" } else { ", (" });
+            out.push_bind_param::<BigInt, _>(m.list)?;
+            out.push_sql(", ");
+            out.push_bind_param::<BigInt, _>(m.domain)?;
+            out.push_sql(", ");
+            out.push_bind_param::<Integer, _>(m.rank)?;
+            out.push_sql(")");
+        }
+        Ok(())
+    }
+}
+
+impl<'a> QueryId for InsertMembers<'a> {
+    type QueryId = ();
+    const HAS_STATIC_QUERY_ID: bool = false;
+}
 
-    return diesel::insert_into(schema::domain::table)
-        .values(&d)
-        .execute(conn);
+impl<'a> RunQueryDsl<SqliteConnection> for InsertMembers<'a> {}
+
+/// Row IDs of those of the domains already in the SQLite database, by FQDN.
+/// Domains are looked up in chunks, as SQLite limits the number of
+/// parameters of a statement to 999.
+///
+/// # Arguments
+/// * `names` - FQDNs of the domains
+/// * `conn` - SQLite connection
+fn domain_rowids(names: &[&str], conn: &SqliteConnection) -> QueryResult<HashMap<String, i64>> {
+    let mut rowids = HashMap::new();
+    for chunk in names.chunks(500) {
+        let found = schema::domain::table
+            .filter(schema::domain::fqdn.eq_any(chunk.to_vec()))
+            .select((schema::domain::fqdn, schema::domain::rowid))
+            .load::<(String, i64)>(conn)?;
+        rowids.extend(found);
+    }
+    Ok(rowids)
+}
+
+/// Rank of those of the domains already in the list, by row ID of the
+/// domain. Domains are looked up in chunks, as for `domain_rowids`.
+///
+/// # Arguments
+/// * `list` - Row ID of the list
+/// * `domains` - Row IDs of the domains
+/// * `conn` - SQLite connection
+fn member_ranks(
+    list: i64,
+    domains: &[i64],
+    conn: &SqliteConnection,
+) -> QueryResult<HashMap<i64, i32>> {
+    let mut ranks = HashMap::new();
+    for chunk in domains.chunks(500) {
+        let found = schema::list_member::table
+            .filter(schema::list_member::list.eq(list))
+            .filter(schema::list_member::domain.eq_any(chunk.to_vec()))
+            .select((schema::list_member::domain, schema::list_member::rank))
+            .load::<(i64, i32)>(conn)?;
+        ranks.extend(found);
+    }
+    Ok(ranks)
 }
@@ -3,27 +3,52 @@ extern crate damp;
 extern crate failure;
 
 use clap::{App, Arg};
-use damp::model::connect;
+use damp::asn::{open_source, AsnMatch, AsnSource};
+use damp::authority::{
+    authoritative_answer, authoritative_servers, check_apex, query_server, rrset_ttl, same_name,
+};
+use damp::cloud::CloudRanges;
+use damp::dns::random_label;
+use damp::dnssec::{dnssec_type, name_key, zone_chain};
+use damp::exchange::{build_query, exchange, scope_prefix, ClientSubnet};
+use damp::manipulation::{assess, Verdict};
+use damp::mmdb::{self, Location};
+use damp::model::answer::NewAnswerSample;
+use damp::model::apex_violation::NewApexViolation;
+use damp::model::asn::NewAsn;
+use damp::model::{connect, last_insert_rowid};
+use damp::model::dnssec::NewDnssecRecord;
 use damp::model::domain::Domain;
-use damp::model::record::{NewRecord, Record};
+use damp::model::manipulation::NewManipulation;
+use damp::model::record::NewRecord;
+use damp::model::wildcard::NewWildcard;
+use damp::rpki::Vrps;
+use damp::special::AddressClassifier;
 use damp::*;
 use diesel::prelude::*;
 use diesel::QueryDsl;
 use failure::Error;
-use maxminddb::geoip2::Isp;
+use maxminddb::geoip2::City;
 use maxminddb::Reader;
-use std::net::SocketAddr;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
 use trust_dns::client::{Client, SyncClient};
-use trust_dns::op::DnsResponse;
+use trust_dns::op::{DnsResponse, Message};
 use trust_dns::rr::{DNSClass, Name, RData, RecordType};
 use trust_dns::udp::UdpClientConnection;
+use trust_dns_proto::rr::dnssec::rdata::DNSSECRecordType;
+use trust_dns_proto::serialize::binary::BinEncodable;
 
 static QUERY_VERSION: &'static str = env!("CARGO_PKG_VERSION");
 static QUERY_AUTHORS: &'static str = env!("CARGO_PKG_AUTHORS");
 static QUERY_ABOUT: &'static str = r#"
 With this binary we take a list of domains in a SQLite database loaded by the
 domain_loader(8) binary and for each run a set of queries against the provided
-resolver:
+resolvers:
 * A
 * AAAA
 * NS
@@ -32,16 +57,92 @@ It is not a requirement that the machine have IPv6 present in order to complete
 the AAAA queries, however the name server in use must answer correctly in spite
 of our network potentially not being IPv6 capable.
 
-For the sakes of keeping the code complexity low, this process is both
+Should the apex answer with a CNAME, which is illegal alongside the zone's SOA
+and NS records, the authoritative servers of the zone are queried directly to
+confirm it and record which other record types coexist with the CNAME.
+
+The ASN of each address is found with --asn-db, by default a MaxMind GeoLite2
+ASN database. With --asn-source, a CAIDA RouteViews prefix2as file (prefix2as)
+or an MRT TABLE_DUMP_V2 RIB dump (mrt) may be used instead, with the source
+recorded alongside each address. Each may be compressed with gzip, bzip2, xz or
+zstd, e.g. the rib.*.bz2 dumps published by RouteViews. Private use ASNs above
+2147483647 are not attributed.
+
+With --geo-db, given a MaxMind GeoLite2 Country or City database, the country
+of each address is recorded too, along with its city and coordinates where the
+database provides them.
+
+With --cloud-ranges, given any number of times as a provider and path, e.g.
+aws:ip-ranges.json, addresses within the ranges published by the cloud
+provider are tagged with the provider, service and region they belong to. The
+providers understood are aws, gcp, azure, oracle and cloudflare.
+
+Every address is classified against the IANA special-purpose registries, e.g.
+loopback, private or documentation space, and flagged as a bogon where it is not
+globally reachable or is within any of the bogon lists given by --bogons, such
+as the Team Cymru full bogons.
+
+With --vrps, given a JSON export of Validated ROA Payloads from an RPKI relying
+party such as Routinator or rpki-client, the route covering each address is
+validated against its origin ASN as valid, invalid or not_found - including
+the addresses of the name servers. The route is the announced prefix matched,
+so an --asn-source of prefix2as or mrt is required, a MaxMind network being no
+route at all.
+
+With --probe-wildcard, a random label under each apex is also queried for A, as
+any answer to it reveals a wildcard record that may be synthesising www.
+
+With --dnssec, the DNSKEY and DS records (with their RRSIGs) of every zone from
+the root to each apex are collected with the DO bit set, along with the apex's
+SOA, for validation by the dnssec_validate(8) binary.
+
+With --ecs-subnet, given any number of times, the A and AAAA queries of the apex
+and www are repeated with each EDNS Client Subnet attached, storing the answers
+alongside the subnet and the scope prefix returned by the resolver.
+
+With --detect-manipulation, the authoritative servers of each zone are asked the
+same A and AAAA queries of the apex and www directly, and the resolver's answer
+given a verdict of whether it has been tampered with, e.g. NXDOMAIN rewritten
+to an ad server or a sinkhole address such as 0.0.0.0 returned.
+
+With --authoritative-ttl, each query of the apex and www is also sent to the
+zone's authoritative servers, storing the TTL they give alongside the resolver's
+so that the age of the resolver's cached answer can be inferred.
+
+With --repeat, the A and AAAA queries of the apex and www are additionally made
+the given number of times, --repeat-interval milliseconds apart, with every
+answer stored for the answer_stability(8) binary to detect load balancing.
+
+Where --resolver is given several times, the same queries are run against each
+resolver at once, one thread per resolver, with every row tagged by the resolver
+that answered it. The resolver_compare(8) binary reports where they disagree.
+
+For the sakes of keeping the code complexity low, this process is otherwise both
 synchronous and blocking in nature and any delays by one query will slow down or
-stop subsequent requests.
+stop subsequent requests to the same resolver.
 "#;
 
 struct DnsQuery {
+    dns_resolver: SocketAddr,
+    resolver_name: String,
     dns_client: SyncClient<UdpClientConnection>,
     sql_client: SqliteConnection,
-    maxmind: Reader<Vec<u8>>,
+    asn_source: Arc<dyn AsnSource>,
+    asns_seen: RefCell<HashSet<i32>>,
+    geo_db: Option<Arc<Reader<Vec<u8>>>>,
+    cloud_ranges: Option<Arc<CloudRanges>>,
+    classifier: Arc<AddressClassifier>,
+    vrps: Option<Arc<Vrps>>,
     query_types: Vec<RecordType>,
+    probe_wildcard: bool,
+    collect_dnssec: bool,
+    dnssec_queried: RefCell<HashSet<String>>,
+    client_subnets: Vec<ClientSubnet>,
+    detect_manipulation: bool,
+    authoritative: RefCell<Option<(i64, Vec<IpAddr>)>>,
+    repeat: u32,
+    repeat_interval: Duration,
+    authoritative_ttl: bool,
 }
 
 impl DnsQuery {
@@ -50,7 +151,7 @@ impl DnsQuery {
     /// # Arguments:
     /// * `dns_resolver`: The socket address to a Do53 service
     /// * `sql_db`: Path to SQLite database
-    /// * `mmdb`: Path to Maxmind GeoLite2 ASN database
+    /// * `asn_source`: Source of the ASN of addresses, shared between threads
     ///
     /// # Example
     /// ```
@@ -58,20 +159,446 @@ impl DnsQuery {
     ///
     /// let addr = "127.0.0.1:53".parse().unwrap();
     /// let path = "tests/fixtures/GeoLite2-ASN-Test.mmdb";
+    /// let source = Arc::from(open_source("mmdb", path).unwrap());
     /// let sql = ":memory:".to_string();
-    /// let d_q = Query::new(addr, sql, path);
+    /// let d_q = Query::new(addr, sql, source);
     /// ```
-    pub fn new(dns_resolver: SocketAddr, sql_db: &str, mmdb: &str) -> Result<DnsQuery, Error> {
+    pub fn new(
+        dns_resolver: SocketAddr,
+        sql_db: &str,
+        asn_source: Arc<dyn AsnSource>,
+    ) -> Result<DnsQuery, Error> {
         let dns_conn = UdpClientConnection::new(dns_resolver).expect("Unable to use DNS client!");
-        let mmdb = Reader::open_readfile(mmdb)?;
         Ok(DnsQuery {
+            dns_resolver,
+            resolver_name: dns_resolver.to_string(),
             dns_client: SyncClient::new(dns_conn),
             sql_client: connect(sql_db.to_string()),
-            maxmind: mmdb,
+            asn_source,
+            asns_seen: RefCell::new(HashSet::new()),
+            geo_db: None,
+            cloud_ranges: None,
+            classifier: Arc::new(AddressClassifier::new()),
+            vrps: None,
             query_types: vec![RecordType::A, RecordType::AAAA, RecordType::NS],
+            probe_wildcard: false,
+            collect_dnssec: false,
+            dnssec_queried: RefCell::new(HashSet::new()),
+            client_subnets: Vec::new(),
+            detect_manipulation: false,
+            authoritative: RefCell::new(None),
+            repeat: 0,
+            repeat_interval: Duration::from_millis(0),
+            authoritative_ttl: false,
         })
     }
 
+    /// Repeat the A and AAAA queries of the apex and www once for each of the
+    /// given EDNS Client Subnets, in addition to the queries made without.
+    ///
+    /// # Arguments
+    /// * `subnets` - Vantage subnets to present to the resolver
+    pub fn client_subnets(mut self, subnets: Vec<ClientSubnet>) -> DnsQuery {
+        self.client_subnets = subnets;
+        self
+    }
+
+    /// Perform a query carrying an EDNS Client Subnet option, persisting the
+    /// answers along with the subnet and the scope prefix the resolver
+    /// returned.
+    ///
+    /// # Arguments
+    /// * `domain` - The [Domain](crate::model::domain::Domain)
+    /// * `query_type` - The DNS RecordType to query
+    /// * `is_www` - If set true, query against 'www.' of the domain
+    /// * `subnet` - The client subnet to attach to the query
+    pub fn query_domain_subnet(
+        &self,
+        domain: &Domain,
+        query_type: RecordType,
+        is_www: bool,
+        subnet: &ClientSubnet,
+    ) {
+        let query: String = match is_www {
+            true => format!("www.{}", domain.fqdn),
+            false => domain.fqdn.clone(),
+        };
+        let name: Name = match Name::from_ascii(query) {
+            Ok(n) => n,
+            Err(e) => {
+                println!("Unable to parse domain - {}", e.to_string());
+                return;
+            }
+        };
+        let mut message = build_query(&name, query_type, true);
+        message.edns_mut().set_option(subnet.to_option());
+
+        let query_time = unix_time();
+        let response = match exchange(self.dns_resolver, &message) {
+            Ok(r) => r,
+            Err(e) => {
+                stall(e.to_string());
+                self.query_domain_subnet(domain, query_type, is_www, subnet);
+                return;
+            }
+        };
+
+        self.insert_record(
+            &response,
+            &domain.rowid,
+            &is_www,
+            &query_time,
+            None,
+            Some(subnet),
+            None,
+        );
+    }
+
+    /// Enable collection of the DNSKEY, DS and RRSIG records needed to
+    /// validate the chain of trust of each apex offline.
+    ///
+    /// # Arguments
+    /// * `enabled` - If set true, collect DNSSEC records for every domain
+    pub fn collect_dnssec(mut self, enabled: bool) -> DnsQuery {
+        self.collect_dnssec = enabled;
+        self
+    }
+
+    /// Query, with the DO bit set, the DNSKEY and DS records of every name
+    /// from the root to the apex and the SOA of the apex. The CD bit is also
+    /// set so that a validating resolver hands us bogus data rather than
+    /// `SERVFAIL`, as we validate it ourselves later. Names shared between
+    /// domains, such as the root and TLDs, are only queried once per run.
+    ///
+    /// # Arguments
+    /// * `domain` - The [Domain](crate::model::domain::Domain)
+    pub fn query_dnssec(&self, domain: &Domain) {
+        let apex: Name = match Name::from_ascii(&domain.fqdn) {
+            Ok(n) => n,
+            Err(e) => {
+                println!("Unable to parse domain - {}", e.to_string());
+                return;
+            }
+        };
+
+        for zone in zone_chain(&apex) {
+            let mut query_types = vec![dnssec_type(DNSSECRecordType::DNSKEY)];
+            if !zone.is_root() {
+                query_types.push(dnssec_type(DNSSECRecordType::DS));
+            }
+            if same_name(&zone, &apex) {
+                query_types.push(RecordType::SOA);
+            }
+
+            for query_type in query_types {
+                let key = format!("{} {}", name_key(&zone), query_type);
+                if !self.dnssec_queried.borrow_mut().insert(key) {
+                    continue;
+                }
+                self.query_dnssec_rrset(&zone, query_type);
+            }
+        }
+    }
+
+    fn query_dnssec_rrset(&self, zone: &Name, query_type: RecordType) {
+        let mut message = build_query(zone, query_type, true);
+        message.set_checking_disabled(true);
+        message.edns_mut().set_dnssec_ok(true);
+
+        let query_time = unix_time();
+        let response = match exchange(self.dns_resolver, &message) {
+            Ok(r) => r,
+            Err(e) => {
+                stall(e.to_string());
+                self.query_dnssec_rrset(zone, query_type);
+                return;
+            }
+        };
+
+        let query_name = name_key(zone);
+        let query_type_name = query_type.to_string();
+        let response_code = response.response_code() as i32;
+        let sections = response
+            .answers()
+            .iter()
+            .map(|r| ("answer", r))
+            .chain(response.name_servers().iter().map(|r| ("authority", r)));
+
+        let mut rows: Vec<(Option<&str>, Option<String>, Option<Vec<u8>>)> = Vec::new();
+        for (section, record) in sections {
+            match record.to_bytes() {
+                Ok(data) => rows.push((
+                    Some(section),
+                    Some(record.record_type().to_string()),
+                    Some(data),
+                )),
+                Err(e) => println!("Unable to encode record - {}", e.to_string()),
+            }
+        }
+        // Persist a row even without records, noting that the query was made
+        if rows.is_empty() {
+            rows.push((None, None, None));
+        }
+
+        for (section, record_type, data) in &rows {
+            let row = NewDnssecRecord {
+                query_name: &query_name,
+                query_type: &query_type_name,
+                response_code: &response_code,
+                section: *section,
+                record_type: record_type.as_ref().map(String::as_str),
+                data: data.as_ref().map(Vec::as_slice),
+                query_time: &query_time,
+                resolver: Some(&self.resolver_name),
+            };
+            match diesel::insert_into(schema::dnssec_record::table)
+                .values(&row)
+                .execute(&self.sql_client)
+            {
+                Ok(_) => {}
+                Err(e) => println!("Unable to insert DNSSEC record - {}", e.to_string()),
+            };
+        }
+    }
+
+    /// Enable comparing the resolver's A and AAAA answers against those of the
+    /// authoritative servers to detect manipulation.
+    ///
+    /// # Arguments
+    /// * `enabled` - If set true, query the authoritative servers as well
+    pub fn detect_manipulation(mut self, enabled: bool) -> DnsQuery {
+        self.detect_manipulation = enabled;
+        self
+    }
+
+    /// Enable asking the authoritative servers the same queries of the apex
+    /// and www, to record the configured TTL next to the resolver's.
+    ///
+    /// # Arguments
+    /// * `enabled` - If set true, query the authoritative servers as well
+    pub fn authoritative_ttl(mut self, enabled: bool) -> DnsQuery {
+        self.authoritative_ttl = enabled;
+        self
+    }
+
+    /// Locate every address answered in a GeoLite2 Country or City database.
+    ///
+    /// # Arguments
+    /// * `geo_db` - The database, shared between threads, or `None` to disable
+    pub fn geo_db(mut self, geo_db: Option<Arc<Reader<Vec<u8>>>>) -> DnsQuery {
+        self.geo_db = geo_db;
+        self
+    }
+
+    /// Tag every address answered within the published ranges of a cloud
+    /// provider with its provider, service and region.
+    ///
+    /// # Arguments
+    /// * `cloud_ranges` - The ranges, shared between threads, or `None` to
+    ///   disable
+    pub fn cloud_ranges(mut self, cloud_ranges: Option<Arc<CloudRanges>>) -> DnsQuery {
+        self.cloud_ranges = cloud_ranges;
+        self
+    }
+
+    /// Classify addresses with the given classifier, holding any bogon lists
+    /// loaded, rather than against the special-purpose registries alone.
+    ///
+    /// # Arguments
+    /// * `classifier` - The classifier, shared between threads
+    pub fn classifier(mut self, classifier: Arc<AddressClassifier>) -> DnsQuery {
+        self.classifier = classifier;
+        self
+    }
+
+    /// Validate the origin of the route covering every address answered.
+    ///
+    /// # Arguments
+    /// * `vrps` - The Validated ROA Payloads, shared between threads, or
+    ///   `None` to disable
+    pub fn vrps(mut self, vrps: Option<Arc<Vrps>>) -> DnsQuery {
+        self.vrps = vrps;
+        self
+    }
+
+    /// The RPKI Route Origin Validation state of the announced route matched
+    /// for an address, or `None` where no VRPs were given, or no announced
+    /// route is known - the ASN source holding none, or matching no prefix.
+    fn validate_origin(&self, matched: &AsnMatch) -> Option<&'static str> {
+        let vrps = self.vrps.as_ref()?;
+        if !self.asn_source.announced() {
+            return None;
+        }
+        let mut parts = matched.prefix.as_ref()?.splitn(2, '/');
+        let network: IpAddr = parts.next()?.parse().ok()?;
+        let length: usize = parts.next()?.parse().ok()?;
+        Some(vrps.validate(network, length, matched.asn as u32).as_str())
+    }
+
+    /// The location of the address, empty where no database was given or the
+    /// address is not in it.
+    fn locate(&self, address: IpAddr) -> Location {
+        match &self.geo_db {
+            Some(reader) => match reader.lookup::<City>(address) {
+                Ok(city) => Location::from(city),
+                Err(_) => Location::default(),
+            },
+            None => Location::default(),
+        }
+    }
+
+    /// Repeat the A and AAAA queries of the apex and www a number of times,
+    /// storing every answer to measure how they vary.
+    ///
+    /// # Arguments
+    /// * `count` - Number of times to query each name, 0 to disable
+    /// * `interval` - Time to wait between each query of the same name
+    pub fn repeat(mut self, count: u32, interval: Duration) -> DnsQuery {
+        self.repeat = count;
+        self.repeat_interval = interval;
+        self
+    }
+
+    /// Query the name the configured number of times, spaced apart, storing
+    /// the addresses of each answer in the order they were received.
+    ///
+    /// # Arguments
+    /// * `domain` - The [Domain](crate::model::domain::Domain)
+    /// * `query_type` - The DNS RecordType to query
+    /// * `is_www` - If set true, query against 'www.' of the domain
+    pub fn query_repeated(&self, domain: &Domain, query_type: RecordType, is_www: bool) {
+        let query: String = match is_www {
+            true => format!("www.{}", domain.fqdn),
+            false => domain.fqdn.clone(),
+        };
+        let name: Name = match Name::from_ascii(query) {
+            Ok(n) => n,
+            Err(e) => {
+                println!("Unable to parse domain - {}", e.to_string());
+                return;
+            }
+        };
+
+        for sample in 0..self.repeat {
+            if sample > 0 {
+                thread::sleep(self.repeat_interval);
+            }
+            let query_time = unix_time();
+            let response: DnsResponse = loop {
+                match self.dns_client.query(&name, DNSClass::IN, query_type) {
+                    Ok(r) => break r,
+                    Err(e) => stall(e.to_string()),
+                }
+            };
+
+            let records: Vec<_> = response
+                .answers()
+                .iter()
+                .filter(|a| a.record_type() == query_type)
+                .collect();
+            let addresses = records
+                .iter()
+                .filter_map(|a| self.parse_address(a.rdata()))
+                .collect::<Vec<String>>()
+                .join(",");
+            let ttl = records.iter().map(|a| a.ttl() as i32).min();
+
+            let row = NewAnswerSample {
+                domain: &domain.rowid,
+                is_www: &is_www,
+                query_type: &query_type.to_string(),
+                resolver: &self.resolver_name,
+                sample: &(sample as i32),
+                response_code: &(response.response_code() as i32),
+                addresses: match addresses.is_empty() {
+                    true => None,
+                    false => Some(addresses.as_str()),
+                },
+                ttl: ttl.as_ref(),
+                query_time: &query_time,
+            };
+            match diesel::insert_into(schema::answer_sample::table)
+                .values(&row)
+                .execute(&self.sql_client)
+            {
+                Ok(_) => {}
+                Err(e) => println!("Unable to insert answer sample - {}", e.to_string()),
+            };
+        }
+    }
+
+    /// Enable querying a random label under each apex to detect wildcards.
+    ///
+    /// # Arguments
+    /// * `enabled` - If set true, perform the wildcard probe for every domain
+    pub fn probe_wildcard(mut self, enabled: bool) -> DnsQuery {
+        self.probe_wildcard = enabled;
+        self
+    }
+
+    /// Query an A record for a random label under the apex of the domain.
+    /// Any answer returned must have been synthesised from a wildcard.
+    ///
+    /// # Arguments
+    /// * `domain` - The [Domain](crate::model::domain::Domain)
+    pub fn query_wildcard(&self, domain: &Domain) {
+        let label = random_label();
+        let name: Name = match Name::from_ascii(format!("{}.{}", label, domain.fqdn)) {
+            Ok(n) => n,
+            Err(e) => {
+                println!("Unable to parse domain - {}", e.to_string());
+                return;
+            }
+        };
+        let query_time = unix_time();
+        let response: DnsResponse = match self.dns_client.query(&name, DNSClass::IN, RecordType::A) {
+            Ok(r) => r,
+            Err(e) => {
+                stall(e.to_string());
+                self.query_wildcard(domain);
+                return;
+            }
+        };
+
+        let response_code = response.response_code() as i32;
+        let is_wildcard = !response.answers().is_empty();
+        let mut rows: Vec<(Option<String>, Option<String>, Option<i32>)> = response
+            .answers()
+            .iter()
+            .map(|a| {
+                (
+                    Some(a.record_type().to_string()),
+                    self.parse_address(a.rdata()),
+                    Some(a.ttl() as i32),
+                )
+            })
+            .collect();
+        if rows.is_empty() {
+            rows.push((None, None, None));
+        }
+
+        for (record_type, address, ttl) in &rows {
+            let wildcard = NewWildcard {
+                domain: &domain.rowid,
+                label: &label,
+                is_wildcard: &is_wildcard,
+                response_code: &response_code,
+                record_type: record_type.as_ref().map(String::as_str),
+                ttl: ttl.as_ref(),
+                address: address.as_ref().map(String::as_str),
+                query_time: &query_time,
+                resolver: Some(&self.resolver_name),
+            };
+            match diesel::insert_into(schema::wildcard::table)
+                .values(&wildcard)
+                .execute(&self.sql_client)
+            {
+                Ok(_) => {}
+                Err(e) => println!("Unable to insert wildcard - {}", e.to_string()),
+            };
+        }
+    }
+
     /// Perform queries of all query types.
     ///
     /// # Arguments
@@ -100,11 +627,44 @@ impl DnsQuery {
             }
         };
 
-        self.insert_record(&response, &domain.rowid, &is_www, &query_time, None);
+        let authoritative = match self.authoritative_ttl {
+            true => authoritative_answer(&self.authoritative_for(domain), &name, query_type),
+            false => None,
+        };
+        self.insert_record(
+            &response,
+            &domain.rowid,
+            &is_www,
+            &query_time,
+            None,
+            None,
+            authoritative.as_ref(),
+        );
 
         let parent_rowid = self.get_last_row();
 
-        // Process NS records and convert the host name returned into A/AAAA records
+        // A CNAME at the apex is illegal, so confirm against the authoritative servers
+        if query_type == RecordType::A && !is_www {
+            let cname_at_apex = response
+                .answers()
+                .iter()
+                .any(|a| a.record_type() == RecordType::CNAME && same_name(a.name(), &name));
+            if cname_at_apex {
+                self.check_apex_violation(domain, &name);
+            }
+        }
+
+        if self.detect_manipulation {
+            match query_type {
+                RecordType::A | RecordType::AAAA => {
+                    self.check_manipulation(domain, &name, query_type, is_www, &response)
+                }
+                _ => {}
+            }
+        }
+
+        // Process NS records and convert the host name returned into A/AAAA
+        // records, stored as children of the NS record with their ASN and ROV
         if query_type == RecordType::NS {
             for answer in response.answers().iter() {
                 query_time = unix_time();
@@ -113,7 +673,7 @@ impl DnsQuery {
                     Ok(n) => n,
                     Err(e) => {
                         println!("Unable to parse NS record - {}", e.to_string());
-                        return;
+                        continue;
                     }
                 };
 
@@ -133,6 +693,8 @@ impl DnsQuery {
                     &false,
                     &query_time,
                     parent_rowid.as_ref(),
+                    None,
+                    None,
                 );
 
                 let aaaa_res: DnsResponse =
@@ -150,31 +712,234 @@ impl DnsQuery {
                     &false,
                     &query_time,
                     parent_rowid.as_ref(),
+                    None,
+                    None,
                 );
             }
         }
     }
 
+    /// Query the authoritative servers of the domain directly to confirm a
+    /// CNAME at the apex, and which other record types coexist with it.
+    ///
+    /// # Arguments
+    /// * `domain` - The [Domain](crate::model::domain::Domain)
+    /// * `apex` - Name of the apex
+    fn check_apex_violation(&self, domain: &Domain, apex: &Name) {
+        let query_time = unix_time();
+        let servers = match authoritative_servers(&self.dns_client, apex) {
+            Ok(s) => s,
+            Err(e) => {
+                println!("Unable to find authoritative servers - {}", e.to_string());
+                return;
+            }
+        };
+        let violation = check_apex(&servers, apex);
+        let other_types = violation
+            .other_types
+            .iter()
+            .map(|t| t.to_string())
+            .collect::<Vec<String>>()
+            .join(",");
+
+        let row = NewApexViolation {
+            domain: &domain.rowid,
+            servers: &(violation.servers as i32),
+            cname_at_apex: &violation.cname_at_apex,
+            cname_with_other_data: &violation.cname_with_other_data(),
+            other_types: match other_types.is_empty() {
+                true => None,
+                false => Some(other_types.as_str()),
+            },
+            query_time: &query_time,
+            resolver: Some(&self.resolver_name),
+        };
+        match diesel::insert_into(schema::apex_violation::table)
+            .values(&row)
+            .execute(&self.sql_client)
+        {
+            Ok(_) => {}
+            Err(e) => println!("Unable to insert apex violation - {}", e.to_string()),
+        };
+    }
+
+    /// Ask the authoritative servers of the domain the same query as was put
+    /// to the resolver, and persist a verdict of whether the resolver's
+    /// response differs from all of theirs.
+    ///
+    /// # Arguments
+    /// * `domain` - The [Domain](crate::model::domain::Domain)
+    /// * `name` - Name queried
+    /// * `query_type` - The DNS RecordType queried
+    /// * `is_www` - Set true if the query was for 'www.' of the domain
+    /// * `response` - Response of the resolver
+    fn check_manipulation(
+        &self,
+        domain: &Domain,
+        name: &Name,
+        query_type: RecordType,
+        is_www: bool,
+        response: &Message,
+    ) {
+        let query_time = unix_time();
+        let servers = self.authoritative_for(domain);
+        let authoritative: Vec<Message> = servers
+            .iter()
+            .filter_map(|s| query_server(*s, name, query_type).ok())
+            .map(Message::from)
+            .collect();
+        let answered = authoritative.iter().filter(|m| m.authoritative()).count();
+        let (verdict, reason) = assess(name, response, &authoritative);
+
+        let row = NewManipulation {
+            domain: &domain.rowid,
+            is_www: &is_www,
+            query_type: &query_type.to_string(),
+            resolver: &self.resolver_name,
+            response_code: &(response.response_code() as i32),
+            servers: &(answered as i32),
+            verdict: verdict.as_str(),
+            reason: match verdict {
+                Verdict::Consistent => None,
+                _ => Some(reason.as_str()),
+            },
+            query_time: &query_time,
+        };
+        match diesel::insert_into(schema::manipulation::table)
+            .values(&row)
+            .execute(&self.sql_client)
+        {
+            Ok(_) => {}
+            Err(e) => println!("Unable to insert manipulation verdict - {}", e.to_string()),
+        };
+    }
+
+    /// The addresses of the domain's authoritative servers, looked up once
+    /// per domain and reused for each of its queries.
+    ///
+    /// # Arguments
+    /// * `domain` - The [Domain](crate::model::domain::Domain)
+    fn authoritative_for(&self, domain: &Domain) -> Vec<IpAddr> {
+        if let Some((rowid, servers)) = &*self.authoritative.borrow() {
+            if *rowid == domain.rowid {
+                return servers.clone();
+            }
+        }
+        let servers = match Name::from_ascii(&domain.fqdn) {
+            Ok(apex) => match authoritative_servers(&self.dns_client, &apex) {
+                Ok(s) => s,
+                Err(e) => {
+                    println!("Unable to find authoritative servers - {}", e.to_string());
+                    Vec::new()
+                }
+            },
+            Err(e) => {
+                println!("Unable to parse domain - {}", e.to_string());
+                Vec::new()
+            }
+        };
+        *self.authoritative.borrow_mut() = Some((domain.rowid, servers.clone()));
+        servers
+    }
+
+    /// Persist each answer of the response as a row of the record table.
+    ///
+    /// # Arguments
+    /// * `response` - Response of the resolver
+    /// * `row_id` - row-id of the domain queried
+    /// * `is_www` - Set true if the query was for 'www.' of the domain
+    /// * `query_time` - Time the query was made
+    /// * `parent` - row-id of the record that led to this query, if any
+    /// * `subnet` - EDNS Client Subnet sent with the query, if any
+    /// * `authoritative` - Response of an authoritative server to the same
+    ///   query, from which the configured TTL of each answer is taken
     fn insert_record(
         &self,
-        response: &DnsResponse,
+        response: &Message,
         row_id: &i64,
         is_www: &bool,
         query_time: &i64,
         parent: Option<&i64>,
+        subnet: Option<&ClientSubnet>,
+        authoritative: Option<&Message>,
     ) {
         let mut parent_record: Option<i64> = parent.cloned();
+        let client_subnet = subnet.map(|s| s.to_string());
+        let scope = subnet.and_then(|_| scope_prefix(response)).map(i32::from);
+
+        // Persist responses without answers (e.g. NXDOMAIN) for their RCODE alone
+        if response.answers().is_empty() {
+            let record = NewRecord {
+                domain: row_id,
+                is_www,
+                parent,
+                response_code: &(response.response_code() as i32),
+                record_type: None,
+                ttl: None,
+                address: None,
+                asn: None,
+                query_time,
+                client_subnet: client_subnet.as_ref().map(String::as_str),
+                scope_prefix: scope.as_ref(),
+                resolver: Some(&self.resolver_name),
+                authoritative_ttl: None,
+                cache_age: None,
+                prefix: None,
+                asn_source: None,
+                country: None,
+                city: None,
+                latitude: None,
+                longitude: None,
+                cloud_provider: None,
+                cloud_service: None,
+                cloud_region: None,
+                special_purpose: None,
+                bogon: None,
+                rov: None,
+            };
+            match diesel::insert_into(schema::record::table)
+                .values(&record)
+                .execute(&self.sql_client)
+            {
+                Ok(_) => {}
+                Err(e) => println!("Unable to insert record - {}", e.to_string()),
+            };
+        }
+
         for answer in response.answers().iter() {
-            let asn: Option<i32> = match answer.rdata().to_ip_addr() {
-                Some(ip) => match self.maxmind.lookup::<Isp>(ip) {
-                    Ok(res) => res.autonomous_system_number.map(|m| m as i32),
-                    Err(_) => None,
-                },
-                None => None,
+            let matched = answer
+                .rdata()
+                .to_ip_addr()
+                .and_then(|ip| self.asn_source.lookup(ip));
+            if let Some(m) = &matched {
+                self.insert_asn(m.asn, m.organisation.as_ref());
+            }
+            let asn = matched.as_ref().map(|m| m.asn);
+            let prefix = matched.as_ref().and_then(|m| m.prefix.clone());
+            let asn_source = matched.as_ref().map(|_| self.asn_source.name());
+            let location = match answer.rdata().to_ip_addr() {
+                Some(ip) => self.locate(ip),
+                None => Location::default(),
+            };
+            let cloud = match (&self.cloud_ranges, answer.rdata().to_ip_addr()) {
+                (Some(ranges), Some(ip)) => ranges.lookup(ip),
+                _ => None,
             };
+            let class = answer
+                .rdata()
+                .to_ip_addr()
+                .map(|ip| self.classifier.classify(ip));
+            let rov = matched.as_ref().and_then(|m| self.validate_origin(m));
             let address = self.parse_address(answer.rdata());
             let record_type = answer.record_type().to_string();
             let ttl = answer.ttl() as i32;
+            // A resolver counts the TTL down from the authoritative value as the
+            // answer sits in its cache. Should it be higher, the resolver has
+            // imposed its own minimum and the age cannot be known.
+            let authoritative_ttl = authoritative
+                .and_then(|a| rrset_ttl(a, answer.name(), answer.record_type()))
+                .map(|t| t as i32);
+            let cache_age = authoritative_ttl.filter(|a| *a >= ttl).map(|a| a - ttl);
             let record = NewRecord {
                 domain: row_id,
                 is_www,
@@ -185,6 +950,23 @@ impl DnsQuery {
                 address: address.as_ref().map(String::as_str),
                 asn: asn.as_ref(),
                 query_time,
+                client_subnet: client_subnet.as_ref().map(String::as_str),
+                scope_prefix: scope.as_ref(),
+                resolver: Some(&self.resolver_name),
+                authoritative_ttl: authoritative_ttl.as_ref(),
+                cache_age: cache_age.as_ref(),
+                prefix: prefix.as_ref().map(String::as_str),
+                asn_source,
+                country: location.country.as_ref().map(String::as_str),
+                city: location.city.as_ref().map(String::as_str),
+                latitude: location.latitude.as_ref(),
+                longitude: location.longitude.as_ref(),
+                cloud_provider: cloud.map(|c| c.provider.as_str()),
+                cloud_service: cloud.and_then(|c| c.service.as_ref()).map(String::as_str),
+                cloud_region: cloud.and_then(|c| c.region.as_ref()).map(String::as_str),
+                special_purpose: class.as_ref().and_then(|c| c.special_purpose),
+                bogon: class.as_ref().map(|c| &c.bogon),
+                rov,
             };
 
             match diesel::insert_into(schema::record::table)
@@ -192,10 +974,12 @@ impl DnsQuery {
                 .execute(&self.sql_client)
             {
                 Ok(_) => {
-                    // Set the parent to the value of the row just inserted. As we're
-                    // presently single-threaded, the 'highest' rowid should be the one.
-                    // It's worth noting that the authors of diesel explicitly oppose to
-                    // the use of `sqlite3_last_insert_rowid`.
+                    // Set the parent to the value of the row just inserted. As several
+                    // resolvers may be queried at once, each with its own connection,
+                    // we ask SQLite for the last rowid inserted by this connection
+                    // rather than taking the 'highest' rowid in the table. The authors
+                    // of diesel explicitly oppose exposing `sqlite3_last_insert_rowid`,
+                    // hence calling the SQL function instead.
                     //
                     // See Also: https://github.com/diesel-rs/diesel/issues/376
                     match parent_record {
@@ -210,13 +994,33 @@ impl DnsQuery {
         }
     }
 
+    /// Persist the organisation name of an ASN the first time it is seen.
+    ///
+    /// # Arguments
+    /// * `number` - Autonomous System Number
+    /// * `organisation` - Name of the organisation operating the AS
+    fn insert_asn(&self, number: i32, organisation: Option<&String>) {
+        if !self.asns_seen.borrow_mut().insert(number) {
+            return;
+        }
+        let row = NewAsn {
+            number: &number,
+            organisation: organisation.map(String::as_str),
+        };
+        // Another resolver's thread, or an earlier run, may have inserted it
+        match diesel::insert_or_ignore_into(schema::asn::table)
+            .values(&row)
+            .execute(&self.sql_client)
+        {
+            Ok(_) => {}
+            Err(e) => println!("Unable to insert ASN - {}", e.to_string()),
+        };
+    }
+
     fn get_last_row(&self) -> Option<i64> {
-        use damp::schema::record::dsl::*;
-        let parent_rowid = record
-            .order(rowid.desc())
-            .first::<Record>(&self.sql_client)
-            .unwrap()
-            .rowid;
+        let parent_rowid = diesel::select(last_insert_rowid)
+            .get_result::<i64>(&self.sql_client)
+            .unwrap();
         return Some(parent_rowid);
     }
 
@@ -241,6 +1045,24 @@ impl DnsQuery {
         let domains = domain.load::<Domain>(&self.sql_client).unwrap();
 
         for d in &domains {
+            if self.probe_wildcard {
+                self.query_wildcard(d);
+            }
+            if self.collect_dnssec {
+                self.query_dnssec(d);
+            }
+            if self.repeat > 0 {
+                for query_type in &[RecordType::A, RecordType::AAAA] {
+                    self.query_repeated(d, *query_type, false);
+                    self.query_repeated(d, *query_type, true);
+                }
+            }
+            for subnet in &self.client_subnets {
+                for query_type in &[RecordType::A, RecordType::AAAA] {
+                    self.query_domain_subnet(d, *query_type, false, subnet);
+                    self.query_domain_subnet(d, *query_type, true, subnet);
+                }
+            }
             for query_type in &self.query_types {
                 self.query_domain(d, *query_type, false);
                 // Perform www queries
@@ -289,39 +1111,225 @@ fn main() -> Result<(), Error> {
         )
         .arg(
             Arg::with_name("asn-db")
-                .help("Path to MaxMind GeoLite2 ASN Database")
+                .help("Path to the ASN source, by default a MaxMind GeoLite2 ASN Database")
                 .long("asn-db")
                 .required(true)
                 .value_name("FILE"),
         )
+        .arg(
+            Arg::with_name("asn-source")
+                .help("Kind of ASN source given by --asn-db")
+                .long("asn-source")
+                .takes_value(true)
+                .possible_values(&["mmdb", "prefix2as", "mrt"])
+                .default_value("mmdb"),
+        )
         .arg(
             Arg::with_name("resolver")
-                .help("IP address of DNS resolver to query, including port.")
+                .help("IP address of DNS resolver to query, including port. May be repeated.")
                 .long("resolver")
                 .takes_value(true)
-                .required(false),
+                .multiple(true)
+                .number_of_values(1)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("probe-wildcard")
+                .help("Query a random label under each apex to detect wildcard records")
+                .long("probe-wildcard"),
+        )
+        .arg(
+            Arg::with_name("dnssec")
+                .help("Collect DNSKEY, DS and RRSIG records for offline validation")
+                .long("dnssec"),
+        )
+        .arg(
+            Arg::with_name("detect-manipulation")
+                .help("Compare A and AAAA answers against the authoritative servers")
+                .long("detect-manipulation"),
+        )
+        .arg(
+            Arg::with_name("geo-db")
+                .help("Path to MaxMind GeoLite2 Country or City Database")
+                .long("geo-db")
+                .takes_value(true)
+                .value_name("FILE"),
+        )
+        .arg(
+            Arg::with_name("cloud-ranges")
+                .help("Cloud provider and path to its published IP ranges, e.g. aws:ip-ranges.json")
+                .long("cloud-ranges")
+                .multiple(true)
+                .number_of_values(1)
+                .value_name("PROVIDER:FILE"),
+        )
+        .arg(
+            Arg::with_name("bogons")
+                .help("Path to a list of bogon prefixes, one per line. May be repeated.")
+                .long("bogons")
+                .multiple(true)
+                .number_of_values(1)
+                .value_name("FILE"),
+        )
+        .arg(
+            Arg::with_name("vrps")
+                .help("Path to a JSON export of RPKI Validated ROA Payloads")
+                .long("vrps")
+                .takes_value(true)
+                .value_name("FILE"),
+        )
+        .arg(
+            Arg::with_name("authoritative-ttl")
+                .help("Query the authoritative servers for the configured TTL of each answer")
+                .long("authoritative-ttl"),
+        )
+        .arg(
+            Arg::with_name("repeat")
+                .help("Number of times to repeat A and AAAA queries to detect load balancing")
+                .long("repeat")
+                .takes_value(true)
+                .value_name("COUNT"),
+        )
+        .arg(
+            Arg::with_name("repeat-interval")
+                .help("Milliseconds to wait between repeated queries")
+                .long("repeat-interval")
+                .takes_value(true)
+                .default_value("1000")
+                .value_name("MS"),
+        )
+        .arg(
+            Arg::with_name("ecs-subnet")
+                .help("EDNS Client Subnet to repeat A and AAAA queries from, e.g. 192.0.2.0/24")
+                .long("ecs-subnet")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .value_name("CIDR"),
         )
         .get_matches();
 
-    let resolver: SocketAddr = matches.value_of("resolver").unwrap().parse().unwrap();
+    let resolvers = matches
+        .values_of("resolver")
+        .unwrap()
+        .map(|r| r.parse())
+        .collect::<Result<Vec<SocketAddr>, _>>()?;
     let sqlite_db = matches.value_of("sqlite-db").unwrap();
     let asn_db = matches.value_of("asn-db").unwrap();
+    let asn_source: Arc<dyn AsnSource> =
+        Arc::from(open_source(matches.value_of("asn-source").unwrap(), asn_db)?);
+    let geo_db = match matches.value_of("geo-db") {
+        Some(path) => Some(Arc::new(mmdb::open_reader(path)?)),
+        None => None,
+    };
+    let cloud_ranges = match matches.values_of("cloud-ranges") {
+        Some(values) => {
+            let mut ranges = CloudRanges::new();
+            for value in values {
+                let mut parts = value.splitn(2, ':');
+                let kind = parts.next().unwrap_or("");
+                let path = parts.next().ok_or_else(|| {
+                    failure::format_err!("Expected PROVIDER:FILE for cloud ranges, got '{}'", value)
+                })?;
+                let count = ranges.load(kind, path)?;
+                println!("Loaded {} {} ranges from {}", count, kind, path);
+            }
+            Some(Arc::new(ranges))
+        }
+        None => None,
+    };
+    let mut classifier = AddressClassifier::new();
+    for path in matches.values_of("bogons").into_iter().flatten() {
+        let count = classifier.load_bogons(path)?;
+        println!("Loaded {} bogon prefixes from {}", count, path);
+    }
+    let classifier = Arc::new(classifier);
+    let vrps = match matches.value_of("vrps") {
+        Some(_) if !asn_source.announced() => {
+            return Err(failure::format_err!(
+                "--vrps requires announced routes, from an --asn-source of prefix2as or mrt"
+            ));
+        }
+        Some(path) => {
+            let vrps = Vrps::from_path(path)?;
+            println!("Loaded VRPs of {} prefixes from {}", vrps.len(), path);
+            Some(Arc::new(vrps))
+        }
+        None => None,
+    };
+    let probe_wildcard = matches.is_present("probe-wildcard");
+    let collect_dnssec = matches.is_present("dnssec");
+    let detect_manipulation = matches.is_present("detect-manipulation");
+    let authoritative_ttl = matches.is_present("authoritative-ttl");
+    let repeat: u32 = match matches.value_of("repeat") {
+        Some(r) => r.parse()?,
+        None => 0,
+    };
+    let repeat_interval =
+        Duration::from_millis(matches.value_of("repeat-interval").unwrap().parse()?);
 
-    let dns_query = DnsQuery::new(resolver, sqlite_db, asn_db)?;
+    let client_subnets = match matches.values_of("ecs-subnet") {
+        Some(values) => values
+            .map(|v| v.parse())
+            .collect::<Result<Vec<ClientSubnet>, Error>>()?,
+        None => Vec::new(),
+    };
 
     // --------------------------
     //     Start of processing
     // --------------------------
+    let resolver_list = resolvers
+        .iter()
+        .map(|r| r.to_string())
+        .collect::<Vec<String>>()
+        .join(", ");
     let start = start_processing_marker(format!(
-        "Querying domains using resolver {} into {}",
-        resolver.to_string(),
-        sqlite_db
+        "Querying domains using resolvers {} into {}",
+        resolver_list, sqlite_db
     ));
 
-    let total_domains = dns_query.total_domains();
-    println!("Processing {} domains", total_domains);
+    // Each resolver is queried by its own thread, with its own connection to
+    // the database, so that all resolvers answer at roughly the same time.
+    let workers: Vec<thread::JoinHandle<Result<(), String>>> = resolvers
+        .into_iter()
+        .map(|resolver| {
+            let sqlite_db = sqlite_db.to_string();
+            let asn_source = asn_source.clone();
+            let client_subnets = client_subnets.clone();
+            let geo_db = geo_db.clone();
+            let cloud_ranges = cloud_ranges.clone();
+            let classifier = classifier.clone();
+            let vrps = vrps.clone();
+            thread::spawn(move || {
+                let dns_query = DnsQuery::new(resolver, &sqlite_db, asn_source)
+                    .map_err(|e| e.to_string())?
+                    .probe_wildcard(probe_wildcard)
+                    .collect_dnssec(collect_dnssec)
+                    .detect_manipulation(detect_manipulation)
+                    .authoritative_ttl(authoritative_ttl)
+                    .geo_db(geo_db)
+                    .cloud_ranges(cloud_ranges)
+                    .classifier(classifier)
+                    .vrps(vrps)
+                    .repeat(repeat, repeat_interval)
+                    .client_subnets(client_subnets);
+
+                let total_domains = dns_query.total_domains();
+                println!("Processing {} domains against {}", total_domains, resolver);
 
-    dns_query.process_all();
+                dns_query.process_all();
+                Ok(())
+            })
+        })
+        .collect();
+
+    for worker in workers {
+        match worker.join() {
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => eprintln!("Unable to query resolver - {}", e),
+            Err(_) => eprintln!("Resolver thread panicked"),
+        }
+    }
 
     // --------------------------
     //       End of processing
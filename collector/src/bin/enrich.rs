@@ -0,0 +1,212 @@
+extern crate clap;
+extern crate damp;
+extern crate diesel;
+extern crate failure;
+
+use clap::{App, Arg};
+use damp::asn::stored_asn;
+use damp::mmdb::{self, build_date, Location, SearchTree};
+use damp::model::connect;
+use damp::model::enrichment::NewEnrichment;
+use damp::*;
+use diesel::prelude::*;
+use failure::Error;
+use maxminddb::geoip2::{City, Isp};
+use maxminddb::Reader;
+use std::collections::{BTreeSet, HashSet};
+use std::net::IpAddr;
+
+static ENRICH_VERSION: &'static str = env!("CARGO_PKG_VERSION");
+static ENRICH_AUTHORS: &'static str = env!("CARGO_PKG_AUTHORS");
+static ENRICH_ABOUT: &'static str = r#"
+Recomputes the attribution of every address already collected - the A and AAAA
+answers of the record, wildcard and answer_sample tables - against the supplied
+MaxMind DBs, without making any DNS queries or altering the DNS data.
+
+Each --mmdb may be an ASN database (GeoLite2-ASN), providing the ASN, its
+organisation and the matched prefix, or a Country or City database
+(GeoLite2-Country, GeoLite2-City), providing the country, city and coordinates.
+Results are stored in the enrichment table against the type and build date of
+the database, with addresses already enriched against the same build skipped.
+"#;
+
+/// An opened MaxMind DB along with its type and build date.
+struct Database {
+    reader: Reader<Vec<u8>>,
+    search_tree: SearchTree,
+    database_type: String,
+    build_date: String,
+}
+
+impl Database {
+    fn open(path: &str) -> Result<Database, Error> {
+        let (reader, search_tree) = mmdb::open(path)?;
+        let database_type = reader.metadata.database_type.clone();
+        let build_date = build_date(&reader.metadata);
+        Ok(Database {
+            reader,
+            search_tree,
+            database_type,
+            build_date,
+        })
+    }
+
+    fn is_asn(&self) -> bool {
+        self.database_type.contains("ASN")
+    }
+
+    /// Look up the address and persist the attribution found, or a row of
+    /// `NULL` values should it not be in the database.
+    fn enrich(&self, address: &str, conn: &SqliteConnection) -> Result<(), Error> {
+        let ip: IpAddr = address.parse()?;
+        let enrich_time = unix_time();
+
+        let (mut asn, mut organisation, mut prefix) = (None, None, None);
+        let mut location = Location::default();
+        if self.is_asn() {
+            if let Ok(isp) = self.reader.lookup::<Isp>(ip) {
+                asn = isp.autonomous_system_number.and_then(stored_asn);
+                organisation = isp.autonomous_system_organization;
+                prefix = self.search_tree.prefix(ip);
+            }
+        } else if let Ok(city) = self.reader.lookup::<City>(ip) {
+            location = Location::from(city);
+        }
+
+        let row = NewEnrichment {
+            address,
+            database_type: &self.database_type,
+            build_date: &self.build_date,
+            asn: asn.as_ref(),
+            organisation: organisation.as_ref().map(String::as_str),
+            prefix: prefix.as_ref().map(String::as_str),
+            country: location.country.as_ref().map(String::as_str),
+            city: location.city.as_ref().map(String::as_str),
+            latitude: location.latitude.as_ref(),
+            longitude: location.longitude.as_ref(),
+            enrich_time: &enrich_time,
+        };
+        diesel::insert_into(schema::enrichment::table)
+            .values(&row)
+            .execute(conn)?;
+        Ok(())
+    }
+}
+
+/// Every distinct A and AAAA address collected.
+///
+/// # Arguments
+/// * `conn` - SQLite connection
+fn collected_addresses(conn: &SqliteConnection) -> Result<BTreeSet<String>, Error> {
+    let mut collected: BTreeSet<String> = BTreeSet::new();
+    {
+        use damp::schema::record::dsl::*;
+        collected.extend(
+            record
+                .filter(record_type.eq_any(vec!["A", "AAAA"]))
+                .select(address)
+                .distinct()
+                .load::<Option<String>>(conn)?
+                .into_iter()
+                .filter_map(|a| a),
+        );
+    }
+    {
+        use damp::schema::wildcard::dsl::*;
+        collected.extend(
+            wildcard
+                .filter(record_type.eq_any(vec!["A", "AAAA"]))
+                .select(address)
+                .distinct()
+                .load::<Option<String>>(conn)?
+                .into_iter()
+                .filter_map(|a| a),
+        );
+    }
+    {
+        use damp::schema::answer_sample::dsl::*;
+        for sample in answer_sample
+            .select(addresses)
+            .distinct()
+            .load::<Option<String>>(conn)?
+            .into_iter()
+            .filter_map(|a| a)
+        {
+            collected.extend(sample.split(',').map(String::from));
+        }
+    }
+    Ok(collected)
+}
+
+fn main() -> Result<(), Error> {
+    let matches = App::new("enrich")
+        .version(ENRICH_VERSION)
+        .author(ENRICH_AUTHORS)
+        .about(ENRICH_ABOUT)
+        .arg(
+            Arg::with_name("sqlite-db")
+                .help("Path to SQLite database")
+                .long("sqlite-db")
+                .required(true)
+                .value_name("FILE"),
+        )
+        .arg(
+            Arg::with_name("mmdb")
+                .help("Path to a MaxMind ASN, Country or City database. May be repeated.")
+                .long("mmdb")
+                .required(true)
+                .multiple(true)
+                .number_of_values(1)
+                .value_name("FILE"),
+        )
+        .get_matches();
+
+    let sqlite_db = matches.value_of("sqlite-db").unwrap();
+    let databases = matches
+        .values_of("mmdb")
+        .unwrap()
+        .map(Database::open)
+        .collect::<Result<Vec<Database>, Error>>()?;
+
+    // --------------------------
+    //     Start of processing
+    // --------------------------
+    let start = start_processing_marker(format!("Enriching addresses in {}", sqlite_db));
+
+    let conn = connect(sqlite_db.to_string());
+    let addresses = collected_addresses(&conn)?;
+
+    for database in &databases {
+        let done: HashSet<String> = {
+            use damp::schema::enrichment::dsl::*;
+            enrichment
+                .filter(database_type.eq(&database.database_type))
+                .filter(build_date.eq(&database.build_date))
+                .select(address)
+                .load::<String>(&conn)?
+                .into_iter()
+                .collect()
+        };
+        let pending: Vec<&String> = addresses.iter().filter(|a| !done.contains(*a)).collect();
+        println!(
+            "Enriching {} addresses against {} built {}",
+            pending.len(),
+            database.database_type,
+            database.build_date
+        );
+
+        for address in &pending {
+            match database.enrich(address, &conn) {
+                Ok(_) => (),
+                Err(e) => eprintln!("Unable to enrich {} - {}", address, e),
+            }
+        }
+    }
+
+    // --------------------------
+    //       End of processing
+    // --------------------------
+    end_processing_marker("Enrichment completed", start);
+
+    Ok(())
+}
@@ -0,0 +1,173 @@
+extern crate clap;
+extern crate damp;
+extern crate diesel;
+extern crate failure;
+
+use clap::{App, Arg};
+use damp::model::connect;
+use damp::model::ptr::NewPtr;
+use damp::*;
+use diesel::prelude::*;
+use failure::Error;
+use std::collections::HashSet;
+use std::net::{IpAddr, SocketAddr};
+use trust_dns::client::{Client, SyncClient};
+use trust_dns::op::DnsResponse;
+use trust_dns::rr::{DNSClass, Name, RData, RecordType};
+use trust_dns::udp::UdpClientConnection;
+
+static PTR_VERSION: &'static str = env!("CARGO_PKG_VERSION");
+static PTR_AUTHORS: &'static str = env!("CARGO_PKG_AUTHORS");
+static PTR_ABOUT: &'static str = r#"
+Looks up the reverse DNS (PTR) of every A and AAAA address collected by the
+domain_query(8) binary - those of the apex, www and the name servers - against
+the provided resolver.
+
+Each address is looked up once, with addresses already present in the ptr table
+from an earlier run skipped, so the binary may be run again after further
+queries to look up only the new addresses.
+"#;
+
+struct PtrLookup {
+    resolver_name: String,
+    dns_client: SyncClient<UdpClientConnection>,
+    sql_client: SqliteConnection,
+}
+
+impl PtrLookup {
+    /// Returns a struct that handles all the various network and DB calls.
+    ///
+    /// # Arguments:
+    /// * `dns_resolver`: The socket address to a Do53 service
+    /// * `sql_db`: Path to SQLite database
+    pub fn new(dns_resolver: SocketAddr, sql_db: &str) -> PtrLookup {
+        let dns_conn = UdpClientConnection::new(dns_resolver).expect("Unable to use DNS client!");
+        PtrLookup {
+            resolver_name: dns_resolver.to_string(),
+            dns_client: SyncClient::new(dns_conn),
+            sql_client: connect(sql_db.to_string()),
+        }
+    }
+
+    /// The A and AAAA addresses collected that have not yet been looked up.
+    pub fn pending_addresses(&self) -> Result<Vec<String>, Error> {
+        let done: HashSet<String> = {
+            use damp::schema::ptr::dsl::*;
+            ptr.select(address)
+                .distinct()
+                .load::<String>(&self.sql_client)?
+                .into_iter()
+                .collect()
+        };
+
+        let addresses = {
+            use damp::schema::record::dsl::*;
+            record
+                .filter(record_type.eq_any(vec!["A", "AAAA"]))
+                .select(address)
+                .distinct()
+                .load::<Option<String>>(&self.sql_client)?
+        };
+        Ok(addresses
+            .into_iter()
+            .filter_map(|a| a)
+            .filter(|a| !done.contains(a))
+            .collect())
+    }
+
+    /// Query the PTR of the address, persisting each host name answered.
+    ///
+    /// # Arguments
+    /// * `address` - IP address, as stored in `record.address`
+    pub fn lookup(&self, address: &str) -> Result<(), Error> {
+        let ip: IpAddr = address.parse()?;
+        let name = Name::from(ip);
+        let query_time = unix_time();
+        let response: DnsResponse = loop {
+            match self.dns_client.query(&name, DNSClass::IN, RecordType::PTR) {
+                Ok(r) => break r,
+                Err(e) => stall(e.to_string()),
+            }
+        };
+
+        let response_code = response.response_code() as i32;
+        let mut rows: Vec<(Option<String>, Option<i32>)> = response
+            .answers()
+            .iter()
+            .filter_map(|a| match a.rdata() {
+                RData::PTR(host) => Some((Some(host.to_ascii()), Some(a.ttl() as i32))),
+                _ => None,
+            })
+            .collect();
+        if rows.is_empty() {
+            rows.push((None, None));
+        }
+
+        for (host, ttl) in &rows {
+            let row = NewPtr {
+                address,
+                response_code: &response_code,
+                host: host.as_ref().map(String::as_str),
+                ttl: ttl.as_ref(),
+                query_time: &query_time,
+                resolver: &self.resolver_name,
+            };
+            diesel::insert_into(schema::ptr::table)
+                .values(&row)
+                .execute(&self.sql_client)?;
+        }
+        Ok(())
+    }
+}
+
+fn main() -> Result<(), Error> {
+    let matches = App::new("ptr_lookup")
+        .version(PTR_VERSION)
+        .author(PTR_AUTHORS)
+        .about(PTR_ABOUT)
+        .arg(
+            Arg::with_name("sqlite-db")
+                .help("Path to SQLite database")
+                .long("sqlite-db")
+                .required(true)
+                .value_name("FILE"),
+        )
+        .arg(
+            Arg::with_name("resolver")
+                .help("IP address of DNS resolver to query, including port.")
+                .long("resolver")
+                .takes_value(true)
+                .required(true),
+        )
+        .get_matches();
+
+    let resolver: SocketAddr = matches.value_of("resolver").unwrap().parse()?;
+    let sqlite_db = matches.value_of("sqlite-db").unwrap();
+
+    let ptr_lookup = PtrLookup::new(resolver, sqlite_db);
+
+    // --------------------------
+    //     Start of processing
+    // --------------------------
+    let start = start_processing_marker(format!(
+        "Looking up PTR of addresses using resolver {} into {}",
+        resolver.to_string(),
+        sqlite_db
+    ));
+
+    let addresses = ptr_lookup.pending_addresses()?;
+    println!("Processing {} addresses", addresses.len());
+    for address in &addresses {
+        match ptr_lookup.lookup(address) {
+            Ok(_) => (),
+            Err(e) => eprintln!("Unable to look up PTR of {} - {}", address, e),
+        }
+    }
+
+    // --------------------------
+    //       End of processing
+    // --------------------------
+    end_processing_marker("Lookup completed", start);
+
+    Ok(())
+}
@@ -0,0 +1,187 @@
+extern crate clap;
+extern crate csv;
+extern crate damp;
+extern crate diesel;
+extern crate failure;
+
+use clap::{App, Arg};
+use csv::Writer;
+use damp::model::connect;
+use damp::model::domain::Domain;
+use damp::model::record::Record;
+use damp::*;
+use diesel::prelude::*;
+use failure::Error;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs::File;
+
+static COMPARE_VERSION: &'static str = env!("CARGO_PKG_VERSION");
+static COMPARE_AUTHORS: &'static str = env!("CARGO_PKG_AUTHORS");
+static COMPARE_ABOUT: &'static str = r#"
+Compares the answers of each resolver queried by the domain_query(8) binary,
+when run with --resolver given several times, reporting where they disagree.
+
+For both the apex and www of each domain, the answers of every resolver are
+reduced to the following aspects:
+* rcode   - the set of RCODEs received
+* address - the set of A and AAAA addresses
+* cname   - the set of CNAME targets
+* ns      - the set of name servers
+
+Answers to queries with an EDNS Client Subnet are ignored, as are the addresses
+of name servers. For every aspect on which the resolvers disagree, a row is
+written to the output CSV per resolver, of the form:
+
+domain,name,aspect,resolver,values
+"#;
+
+/// Aspects of the answers for a single name, keyed by aspect and then by
+/// resolver.
+type Aspects = BTreeMap<&'static str, BTreeMap<String, BTreeSet<String>>>;
+
+fn main() -> Result<(), Error> {
+    let matches = App::new("resolver_compare")
+        .version(COMPARE_VERSION)
+        .author(COMPARE_AUTHORS)
+        .about(COMPARE_ABOUT)
+        .arg(
+            Arg::with_name("sqlite-db")
+                .help("Path to SQLite database")
+                .long("sqlite-db")
+                .required(true)
+                .value_name("FILE"),
+        )
+        .arg(
+            Arg::with_name("output")
+                .help("Path to write the CSV of disagreements to")
+                .long("output")
+                .required(true)
+                .value_name("FILE"),
+        )
+        .get_matches();
+
+    let sqlite_db = matches.value_of("sqlite-db").unwrap();
+    let output = matches.value_of("output").unwrap();
+
+    // --------------------------
+    //     Start of processing
+    // --------------------------
+    let start = start_processing_marker(format!(
+        "Comparing resolvers in {} into {}",
+        sqlite_db, output
+    ));
+
+    let conn = connect(sqlite_db.to_string());
+    let domains = schema::domain::table.load::<Domain>(&conn)?;
+
+    let mut writer = Writer::from_path(output)?;
+    writer.write_record(&["domain", "name", "aspect", "resolver", "values"])?;
+    for d in &domains {
+        match compare_domain(d, &conn, &mut writer) {
+            Ok(_) => (),
+            Err(e) => eprintln!("Unable to compare resolvers for {} - {}", d.fqdn, e),
+        }
+    }
+    writer.flush()?;
+
+    // --------------------------
+    //       End of processing
+    // --------------------------
+    end_processing_marker("Comparison completed", start);
+
+    Ok(())
+}
+
+/// Write the disagreements between resolvers for the apex and www of the
+/// domain.
+///
+/// # Arguments
+/// * `d` - The [Domain](damp::model::domain::Domain)
+/// * `conn` - SQLite connection
+/// * `writer` - CSV writer of the disagreements
+fn compare_domain(
+    d: &Domain,
+    conn: &SqliteConnection,
+    writer: &mut Writer<File>,
+) -> Result<(), Error> {
+    use damp::schema::record::dsl::*;
+    let records = record
+        .filter(domain.eq(d.rowid))
+        .filter(client_subnet.is_null())
+        .load::<Record>(conn)?;
+
+    // Exclude addresses of the name servers, which are children of NS records
+    let ns_rows: HashSet<i64> = records
+        .iter()
+        .filter(|r| r.record_type.as_ref().map(String::as_str) == Some("NS"))
+        .map(|r| r.rowid)
+        .collect();
+
+    for www in &[false, true] {
+        let name = match *www {
+            true => format!("www.{}", d.fqdn),
+            false => d.fqdn.clone(),
+        };
+        let mut aspects = Aspects::new();
+        for r in records.iter().filter(|r| r.is_www == *www).filter(|r| {
+            r.record_type.as_ref().map(String::as_str) == Some("NS")
+                || r.parent.map_or(true, |p| !ns_rows.contains(&p))
+        }) {
+            let resolver_name = r.resolver.clone().unwrap_or_else(|| "unknown".to_string());
+            aspects
+                .entry("rcode")
+                .or_insert_with(BTreeMap::new)
+                .entry(resolver_name.clone())
+                .or_insert_with(BTreeSet::new)
+                .insert(r.response_code.to_string());
+
+            let aspect = match r.record_type.as_ref().map(String::as_str) {
+                Some("A") | Some("AAAA") => "address",
+                Some("CNAME") => "cname",
+                Some("NS") => "ns",
+                _ => continue,
+            };
+            if let Some(value) = &r.address {
+                aspects
+                    .entry(aspect)
+                    .or_insert_with(BTreeMap::new)
+                    .entry(resolver_name)
+                    .or_insert_with(BTreeSet::new)
+                    .insert(value.to_ascii_lowercase());
+            }
+        }
+        write_disagreements(&d.fqdn, &name, &aspects, writer)?;
+    }
+    Ok(())
+}
+
+/// Write a row per resolver for every aspect on which the resolvers do not
+/// all agree. A resolver without any values for an aspect, e.g. no CNAME,
+/// disagrees with one that has them.
+fn write_disagreements(
+    fqdn: &str,
+    name: &str,
+    aspects: &Aspects,
+    writer: &mut Writer<File>,
+) -> Result<(), Error> {
+    let resolvers: BTreeSet<&String> = aspects.values().flat_map(|a| a.keys()).collect();
+    if resolvers.len() < 2 {
+        return Ok(());
+    }
+
+    let empty = BTreeSet::new();
+    for (aspect, by_resolver) in aspects {
+        let values: Vec<&BTreeSet<String>> = resolvers
+            .iter()
+            .map(|r| by_resolver.get(*r).unwrap_or(&empty))
+            .collect();
+        if values.iter().all(|v| *v == values[0]) {
+            continue;
+        }
+        for (resolver, v) in resolvers.iter().zip(values) {
+            let joined = v.iter().cloned().collect::<Vec<String>>().join(" ");
+            writer.write_record(&[fqdn, name, *aspect, resolver.as_str(), joined.as_str()])?;
+        }
+    }
+    Ok(())
+}
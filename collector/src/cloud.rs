@@ -0,0 +1,222 @@
+use crate::asn::PrefixTrie;
+use failure::Error;
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Read};
+use std::net::IpAddr;
+use std::path::Path;
+
+/// The cloud service and region an address range is published for.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CloudRange {
+    /// The cloud provider, e.g. "AWS"
+    pub provider: String,
+    /// The service the range is used by, e.g. "CLOUDFRONT", where published
+    pub service: Option<String>,
+    /// The region the range is used in, e.g. "eu-west-1", where published
+    pub region: Option<String>,
+}
+
+/// The address ranges published by cloud providers, used to tell apart the
+/// many services sharing a provider's ASNs. Each provider's own file is
+/// loaded from disk, as downloaded by `fetch_resources.sh`:
+/// * `aws` - AWS `ip-ranges.json`
+/// * `gcp` - Google Cloud `cloud.json`
+/// * `azure` - Azure Service Tags, `ServiceTags_Public_*.json`
+/// * `oracle` - Oracle Cloud `public_ip_ranges.json`
+/// * `cloudflare` - Cloudflare `ips-v4` and `ips-v6`, one CIDR per line
+///
+/// Where ranges overlap, the longest prefix is taken, with a range published
+/// for a specific service preferred over the same range published for the
+/// provider as a whole (e.g. AWS "AMAZON" or Azure "AzureCloud").
+#[derive(Default)]
+pub struct CloudRanges {
+    trie: PrefixTrie<CloudRange>,
+}
+
+impl CloudRanges {
+    pub fn new() -> CloudRanges {
+        CloudRanges::default()
+    }
+
+    /// Load the ranges of a provider from a file on disk, returning the
+    /// number of ranges loaded.
+    ///
+    /// # Arguments
+    /// * `kind` - One of "aws", "gcp", "azure", "oracle" or "cloudflare"
+    /// * `path` - Path to the provider's published file
+    pub fn load<P: AsRef<Path>>(&mut self, kind: &str, path: P) -> Result<usize, Error> {
+        let file = crate::compress::open(path)?;
+        self.load_reader(kind, file)
+    }
+
+    /// Load the ranges of a provider from anything implementing `Read`.
+    ///
+    /// ```
+    /// use damp::cloud::CloudRanges;
+    ///
+    /// let json = r#"{"prefixes": [
+    ///     {"ip_prefix": "52.84.0.0/15", "region": "GLOBAL", "service": "AMAZON"},
+    ///     {"ip_prefix": "52.84.0.0/15", "region": "GLOBAL", "service": "CLOUDFRONT"},
+    ///     {"ip_prefix": "3.5.140.0/22", "region": "ap-northeast-2", "service": "AMAZON"}
+    /// ], "ipv6_prefixes": []}"#;
+    /// let mut ranges = CloudRanges::new();
+    /// assert_eq!(ranges.load_reader("aws", json.as_bytes()).unwrap(), 3);
+    ///
+    /// let range = ranges.lookup("52.85.1.2".parse().unwrap()).unwrap();
+    /// assert_eq!(range.provider, "AWS");
+    /// assert_eq!(range.service, Some("CLOUDFRONT".to_string()));
+    /// assert_eq!(range.region, Some("GLOBAL".to_string()));
+    /// assert_eq!(ranges.lookup("192.0.2.1".parse().unwrap()), None);
+    /// ```
+    pub fn load_reader<R: Read>(&mut self, kind: &str, reader: R) -> Result<usize, Error> {
+        let ranges = match kind {
+            "aws" => aws_ranges(&serde_json::from_reader(reader)?),
+            "gcp" => gcp_ranges(&serde_json::from_reader(reader)?),
+            "azure" => azure_ranges(&serde_json::from_reader(reader)?),
+            "oracle" => oracle_ranges(&serde_json::from_reader(reader)?),
+            "cloudflare" => cloudflare_ranges(reader)?,
+            _ => return Err(failure::format_err!("Unknown cloud provider '{}'", kind)),
+        };
+
+        // Ranges of the provider as a whole go in first, to be replaced by the
+        // same range of a specific service
+        let count = ranges.len();
+        let (general, specific): (Vec<Range>, Vec<Range>) =
+            ranges.into_iter().partition(|r| r.general);
+        for r in general.into_iter().chain(specific) {
+            self.trie.insert(r.network, r.length, r.range);
+        }
+        Ok(count)
+    }
+
+    /// The range containing the address, or `None` where it belongs to none
+    /// of the providers loaded.
+    pub fn lookup(&self, address: IpAddr) -> Option<&CloudRange> {
+        self.trie.longest_match(address).map(|(_, range)| range)
+    }
+}
+
+/// A range as parsed from a provider's file.
+struct Range {
+    network: IpAddr,
+    length: usize,
+    range: CloudRange,
+    /// Whether the range is published for the provider as a whole
+    general: bool,
+}
+
+impl Range {
+    fn new(
+        cidr: &str,
+        provider: &str,
+        service: Option<&str>,
+        region: Option<&str>,
+    ) -> Option<Range> {
+        let mut parts = cidr.trim().splitn(2, '/');
+        let network: IpAddr = parts.next()?.parse().ok()?;
+        let length: usize = parts.next()?.parse().ok()?;
+        let non_empty = |s: Option<&str>| s.filter(|s| !s.is_empty()).map(String::from);
+        Some(Range {
+            network,
+            length,
+            range: CloudRange {
+                provider: provider.to_string(),
+                service: non_empty(service),
+                region: non_empty(region),
+            },
+            general: false,
+        })
+    }
+}
+
+/// AWS, with `prefixes` of `ip_prefix` and `ipv6_prefixes` of `ipv6_prefix`.
+/// Every range is also published under the service "AMAZON".
+fn aws_ranges(json: &Value) -> Vec<Range> {
+    let mut ranges = Vec::new();
+    for (list, key) in &[("prefixes", "ip_prefix"), ("ipv6_prefixes", "ipv6_prefix")] {
+        for p in json[*list].as_array().into_iter().flatten() {
+            let service = p["service"].as_str();
+            if let Some(mut r) = p[*key]
+                .as_str()
+                .and_then(|c| Range::new(c, "AWS", service, p["region"].as_str()))
+            {
+                r.general = service == Some("AMAZON");
+                ranges.push(r);
+            }
+        }
+    }
+    ranges
+}
+
+/// Google Cloud, with `prefixes` of either `ipv4Prefix` or `ipv6Prefix`,
+/// the region given as the `scope`.
+fn gcp_ranges(json: &Value) -> Vec<Range> {
+    json["prefixes"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|p| {
+            let cidr = p["ipv4Prefix"]
+                .as_str()
+                .or_else(|| p["ipv6Prefix"].as_str())?;
+            Range::new(cidr, "GCP", p["service"].as_str(), p["scope"].as_str())
+        })
+        .collect()
+}
+
+/// Azure, with `values` of service tags, e.g. "AzureFrontDoor.Frontend",
+/// each with `properties` of `addressPrefixes`, `systemService` and `region`.
+/// The tags without a system service, e.g. "AzureCloud.eastus", cover the
+/// provider as a whole.
+fn azure_ranges(json: &Value) -> Vec<Range> {
+    let mut ranges = Vec::new();
+    for tag in json["values"].as_array().into_iter().flatten() {
+        let properties = &tag["properties"];
+        let service = properties["systemService"]
+            .as_str()
+            .filter(|s| !s.is_empty());
+        for cidr in properties["addressPrefixes"]
+            .as_array()
+            .into_iter()
+            .flatten()
+        {
+            if let Some(mut r) = cidr
+                .as_str()
+                .and_then(|c| Range::new(c, "Azure", service, properties["region"].as_str()))
+            {
+                r.general = service.is_none();
+                ranges.push(r);
+            }
+        }
+    }
+    ranges
+}
+
+/// Oracle Cloud, with `regions` each having `cidrs` of a `cidr` and `tags`,
+/// the first tag (e.g. "OCI" or "OBJECT_STORAGE") taken as the service.
+fn oracle_ranges(json: &Value) -> Vec<Range> {
+    let mut ranges = Vec::new();
+    for region in json["regions"].as_array().into_iter().flatten() {
+        for cidr in region["cidrs"].as_array().into_iter().flatten() {
+            let service = cidr["tags"][0].as_str();
+            if let Some(r) = cidr["cidr"]
+                .as_str()
+                .and_then(|c| Range::new(c, "Oracle", service, region["region"].as_str()))
+            {
+                ranges.push(r);
+            }
+        }
+    }
+    ranges
+}
+
+/// Cloudflare, one CIDR per line with neither service nor region.
+fn cloudflare_ranges<R: Read>(reader: R) -> Result<Vec<Range>, Error> {
+    let mut ranges = Vec::new();
+    for line in BufReader::new(reader).lines() {
+        if let Some(r) = Range::new(&line?, "Cloudflare", None, None) {
+            ranges.push(r);
+        }
+    }
+    Ok(ranges)
+}
@@ -0,0 +1,108 @@
+use bzip2::read::BzDecoder;
+use failure::Error;
+use flate2::read::MultiGzDecoder;
+use std::fs::File;
+use std::io::{BufReader, Cursor, Read, Seek};
+use std::path::Path;
+use tar::Archive;
+use xz2::read::XzDecoder;
+use zip::ZipArchive;
+
+/// Open a file for reading, decompressing it as it is read according to its
+/// extension - `.gz`, `.bz2`, `.xz`, `.zst` or `.zip` - without extracting it
+/// to disk. Files with any other extension are read as they are. A zip
+/// archive, or a tar archive within any of the compressions (e.g. `.tar.gz`),
+/// is read as its first file.
+///
+/// ```
+/// use damp::compress;
+/// use std::io::Read;
+///
+/// let mut contents = String::new();
+/// compress::open("Cargo.toml").unwrap().read_to_string(&mut contents).unwrap();
+/// assert!(contents.starts_with("[package]"));
+/// ```
+pub fn open<P: AsRef<Path>>(path: P) -> Result<Box<dyn Read>, Error> {
+    open_member(path, "")
+}
+
+/// Open a file for reading as `open` does, taking the first file of a zip or
+/// tar archive whose name ends in the given suffix, e.g. ".mmdb" to find the
+/// database among the licence and copyright of a GeoLite2 tarball. The file
+/// wanted from an archive is read into memory, other files being streamed.
+///
+/// # Arguments
+/// * `path` - Path to the file
+/// * `suffix` - Suffix of the name of the file wanted within an archive
+pub fn open_member<P: AsRef<Path>>(path: P, suffix: &str) -> Result<Box<dyn Read>, Error> {
+    let path = path.as_ref();
+    let name = path
+        .file_name()
+        .map(|f| f.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let file = BufReader::new(File::open(path)?);
+
+    let reader: Box<dyn Read> = if name.ends_with(".gz") || name.ends_with(".tgz") {
+        Box::new(MultiGzDecoder::new(file))
+    } else if name.ends_with(".bz2") {
+        Box::new(BzDecoder::new(file))
+    } else if name.ends_with(".xz") {
+        Box::new(XzDecoder::new(file))
+    } else if name.ends_with(".zst") {
+        Box::new(zstd::stream::read::Decoder::new(file)?)
+    } else if name.ends_with(".zip") {
+        return zip_member(file, suffix);
+    } else {
+        Box::new(file)
+    };
+
+    let tar = [".tar", ".tgz", ".tar.gz", ".tar.bz2", ".tar.xz", ".tar.zst"]
+        .iter()
+        .any(|e| name.ends_with(e));
+    match tar {
+        true => tar_member(reader, suffix),
+        false => Ok(reader),
+    }
+}
+
+/// Read the whole of a file, decompressing it as `open_member` does, for
+/// those readers that need it in memory such as a MaxMind DB.
+pub fn read_member<P: AsRef<Path>>(path: P, suffix: &str) -> Result<Vec<u8>, Error> {
+    let mut buf = Vec::new();
+    open_member(path, suffix)?.read_to_end(&mut buf)?;
+    Ok(buf)
+}
+
+/// Read the first file of a zip archive whose name ends in the suffix into
+/// memory. The archive is read by its central directory, so that entries
+/// whose sizes follow their data and ZIP64 archives are handled.
+fn zip_member<R: Read + Seek>(reader: R, suffix: &str) -> Result<Box<dyn Read>, Error> {
+    let mut archive = ZipArchive::new(reader)?;
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        if file.is_file() && file.name().ends_with(suffix) {
+            let mut buf = Vec::with_capacity(file.size() as usize);
+            file.read_to_end(&mut buf)?;
+            return Ok(Box::new(Cursor::new(buf)));
+        }
+    }
+    Err(failure::format_err!("No file ending {} in zip", suffix))
+}
+
+/// Read the first regular file of a tar archive whose name ends in the
+/// suffix into memory. GNU long names and PAX extended headers are followed
+/// for the name of each entry.
+fn tar_member(reader: Box<dyn Read>, suffix: &str) -> Result<Box<dyn Read>, Error> {
+    let mut archive = Archive::new(reader);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let wanted = entry.header().entry_type().is_file()
+            && entry.path()?.to_string_lossy().ends_with(suffix);
+        if wanted {
+            let mut buf = Vec::with_capacity(entry.size() as usize);
+            entry.read_to_end(&mut buf)?;
+            return Ok(Box::new(Cursor::new(buf)));
+        }
+    }
+    Err(failure::format_err!("No file ending {} in tar", suffix))
+}
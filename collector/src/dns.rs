@@ -1,6 +1,9 @@
 extern crate publicsuffix;
 
 use publicsuffix::Domain;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::SystemTime;
 
 /// The `publicsuffix` crate does not provide the means to separate a
 /// sub-domain from a root domain, which we need when performing tasks against
@@ -57,3 +60,41 @@ pub fn get_root_domain(domain: &Domain) -> Option<String> {
     let root_len = root_domain.len() - suf_len - 1;
     return Some(root_domain.chars().take(root_len).collect());
 }
+
+/// Generate a random label, formatted as a UUID, which is exceedingly unlikely
+/// to exist under any zone. Querying for such a label reveals whether the zone
+/// synthesises answers from a wildcard record.
+///
+/// ```
+/// use damp::dns::random_label;
+///
+/// let label = random_label();
+/// assert_eq!(label.len(), 36);
+/// assert_ne!(label, random_label());
+/// ```
+pub fn random_label() -> String {
+    let high = random_u64();
+    let low = random_u64();
+    format!(
+        "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+        high >> 32,
+        (high >> 16) & 0xffff,
+        high & 0xffff,
+        low >> 48,
+        low & 0xffff_ffff_ffff
+    )
+}
+
+/// The standard library seeds every `RandomState` with fresh random keys,
+/// which is sufficient for generating labels and message IDs without pulling
+/// in a crate.
+pub(crate) fn random_u64() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos() as u64,
+    );
+    hasher.finish()
+}
@@ -0,0 +1,656 @@
+use super::authority::same_name;
+use failure::Error;
+use std::collections::HashSet;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use trust_dns::rr::{DNSClass, Name, RData, Record, RecordType};
+use trust_dns_proto::rr::dnssec::rdata::{
+    DNSSECRData, DNSSECRecordType, DNSKEY, DS, NSEC, NSEC3, SIG,
+};
+use trust_dns_proto::rr::dnssec::{Algorithm, DigestType, Verifier};
+
+/// Outcome of validating the chain of trust for a zone, following the states
+/// described in [RFC 4035 &sect; 4.3](https://tools.ietf.org/html/rfc4035#section-4.3).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Status {
+    /// An unbroken chain of signed DS and DNSKEY records leads from the
+    /// trust anchor to the zone.
+    Secure,
+    /// The chain of trust ends with a proof that a zone along it is unsigned.
+    Insecure,
+    /// A signature, digest or denial of existence along the chain failed to
+    /// validate.
+    Bogus,
+    /// Not enough data was collected to reach any conclusion.
+    Indeterminate,
+}
+
+impl Status {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Status::Secure => "secure",
+            Status::Insecure => "insecure",
+            Status::Bogus => "bogus",
+            Status::Indeterminate => "indeterminate",
+        }
+    }
+}
+
+/// A validation status along with a human readable reason for it.
+#[derive(Debug)]
+pub struct Validation {
+    pub status: Status,
+    pub reason: String,
+}
+
+impl Validation {
+    fn new(status: Status, reason: String) -> Validation {
+        Validation { status, reason }
+    }
+}
+
+/// The RecordType of a DNSSEC specific record.
+pub fn dnssec_type(record_type: DNSSECRecordType) -> RecordType {
+    RecordType::DNSSEC(record_type)
+}
+
+/// Normalise a name for comparison and persistence - lower case and without
+/// the trailing dot, save for the root which is always `.`.
+///
+/// ```
+/// use damp::dnssec::name_key;
+/// use trust_dns::rr::Name;
+///
+/// assert_eq!(name_key(&Name::from_ascii("Example.COM.").unwrap()), "example.com");
+/// assert_eq!(name_key(&Name::root()), ".");
+/// ```
+pub fn name_key(name: &Name) -> String {
+    let key = name.to_ascii().to_ascii_lowercase();
+    match key.trim_end_matches('.') {
+        "" => ".".to_string(),
+        k => k.to_string(),
+    }
+}
+
+/// Every name from the root down to and including the given name, each of
+/// which may be a zone cut along the chain of trust.
+///
+/// ```
+/// use damp::dnssec::{name_key, zone_chain};
+/// use trust_dns::rr::Name;
+///
+/// let chain = zone_chain(&Name::from_ascii("example.co.uk").unwrap());
+/// let keys: Vec<String> = chain.iter().map(name_key).collect();
+/// assert_eq!(keys, vec![".", "uk", "co.uk", "example.co.uk"]);
+/// ```
+pub fn zone_chain(name: &Name) -> Vec<Name> {
+    let mut chain = vec![Name::root()];
+    for labels in 1..=name.num_labels() {
+        chain.push(name.trim_to(labels as usize));
+    }
+    chain
+}
+
+/// Parse a trust anchor given as a DS record in presentation format, as
+/// published by IANA for the root zone, e.g.
+/// `. IN DS 20326 8 2 E06D44B8...`. The digest may be split by whitespace.
+///
+/// ```
+/// use damp::dnssec::parse_trust_anchor;
+///
+/// let (name, ds) = parse_trust_anchor(
+///     ". IN DS 20326 8 2 E06D44B80B8F1D39A95C0B0D7C65D08458E880409BBC683457104237C7F8EC8D",
+/// ).unwrap();
+/// assert!(name.is_root());
+/// assert_eq!(ds.key_tag(), 20326);
+/// ```
+pub fn parse_trust_anchor(line: &str) -> Result<(Name, DS), Error> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let ds_index = match tokens.iter().position(|t| t.eq_ignore_ascii_case("DS")) {
+        Some(i) if i > 0 && tokens.len() >= i + 5 => i,
+        _ => return Err(failure::format_err!("Invalid trust anchor '{}'", line)),
+    };
+    let name = Name::from_ascii(tokens[0])?;
+    let key_tag: u16 = tokens[ds_index + 1].parse()?;
+    let algorithm = Algorithm::from_u8(tokens[ds_index + 2].parse()?);
+    let digest_type = DigestType::from_u8(tokens[ds_index + 3].parse()?)?;
+    let digest = decode_hex(&tokens[ds_index + 4..].concat())?;
+    Ok((name, DS::new(key_tag, algorithm, digest_type, digest)))
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>, Error> {
+    if hex.len() % 2 != 0 {
+        return Err(failure::format_err!("Odd length hex digest '{}'", hex));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&hex[i..i + 2], 16)
+                .map_err(|_| failure::format_err!("Invalid hex digest '{}'", hex))
+        })
+        .collect()
+}
+
+/// Records collected for validation, keyed by the query which returned them.
+/// Both the answer and authority sections of each response are kept, the
+/// latter carrying any NSEC/NSEC3 proofs that a DS record does not exist.
+#[derive(Default)]
+pub struct RecordStore {
+    queried: HashSet<(String, RecordType)>,
+    records: Vec<(String, RecordType, Record)>,
+}
+
+impl RecordStore {
+    pub fn new() -> RecordStore {
+        RecordStore::default()
+    }
+
+    /// Note that a query was made, even if it returned no records.
+    ///
+    /// # Arguments
+    /// * `name` - Name queried
+    /// * `query_type` - The DNS RecordType queried
+    pub fn mark_queried(&mut self, name: &Name, query_type: RecordType) {
+        self.queried.insert((name_key(name), query_type));
+    }
+
+    /// Add a record returned by a query. The same record returned more than
+    /// once, e.g. by several resolvers, is only kept once.
+    ///
+    /// # Arguments
+    /// * `name` - Name queried
+    /// * `query_type` - The DNS RecordType queried
+    /// * `record` - A record from the answer or authority section
+    pub fn insert(&mut self, name: &Name, query_type: RecordType, record: Record) {
+        self.mark_queried(name, query_type);
+        let entry = (name_key(name), query_type, record);
+        if !self.records.contains(&entry) {
+            self.records.push(entry);
+        }
+    }
+
+    fn was_queried(&self, name: &Name, query_type: RecordType) -> bool {
+        self.queried.contains(&(name_key(name), query_type))
+    }
+
+    fn response<'a>(
+        &'a self,
+        name: &Name,
+        query_type: RecordType,
+    ) -> impl Iterator<Item = &'a Record> + 'a {
+        let key = name_key(name);
+        self.records
+            .iter()
+            .filter(move |(n, t, _)| *n == key && *t == query_type)
+            .map(|(_, _, r)| r)
+    }
+
+    /// Records of the given owner and type returned by a query
+    fn rrset(
+        &self,
+        name: &Name,
+        query_type: RecordType,
+        owner: &Name,
+        record_type: RecordType,
+    ) -> Vec<Record> {
+        self.response(name, query_type)
+            .filter(|r| r.record_type() == record_type && same_name(r.name(), owner))
+            .cloned()
+            .collect()
+    }
+
+    /// Signatures covering records of the given owner and type returned by a query
+    fn rrsigs(
+        &self,
+        name: &Name,
+        query_type: RecordType,
+        owner: &Name,
+        covered: RecordType,
+    ) -> Vec<SIG> {
+        self.response(name, query_type)
+            .filter(|r| same_name(r.name(), owner))
+            .filter_map(|r| match r.rdata() {
+                RData::DNSSEC(DNSSECRData::SIG(sig)) if sig.type_covered() == covered => {
+                    Some(sig.clone())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Validates the chain of trust of zones from a configured trust anchor using
+/// only previously collected records, so that validation may be repeated
+/// offline and against locally signed fixtures.
+pub struct Validator {
+    anchors: Vec<(Name, DS)>,
+}
+
+impl Validator {
+    /// Load trust anchors from a file of DS records in presentation format,
+    /// one per line. Blank lines and those starting with `;` or `#` are
+    /// ignored.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the trust anchor file
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Validator, Error> {
+        let file = std::fs::File::open(path)?;
+        Validator::from_reader(file)
+    }
+
+    /// Load trust anchors from anything implementing `Read`.
+    pub fn from_reader<R: Read>(reader: R) -> Result<Validator, Error> {
+        let mut anchors: Vec<(Name, DS)> = Vec::new();
+        for line in BufReader::new(reader).lines() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+                continue;
+            }
+            anchors.push(parse_trust_anchor(line)?);
+        }
+        Ok(Validator { anchors })
+    }
+
+    /// Validate the chain of trust from the root down to the apex, and the
+    /// apex's SOA record set.
+    ///
+    /// # Arguments
+    /// * `store` - Records collected for every name in the chain
+    /// * `apex` - Apex of the zone to validate
+    /// * `time` - Time, as seconds from UNIX Epoch, against which signature
+    ///   validity periods are checked - usually when the data was collected
+    ///
+    /// ```
+    /// use damp::dnssec::{RecordStore, Status, Validator};
+    /// use trust_dns::rr::Name;
+    ///
+    /// let anchor = ". IN DS 20326 8 2 E06D44B80B8F1D39A95C0B0D7C65D08458E880409BBC683457104237C7F8EC8D";
+    /// let validator = Validator::from_reader(anchor.as_bytes()).unwrap();
+    /// let apex = Name::from_ascii("example.com").unwrap();
+    /// let result = validator.validate(&RecordStore::new(), &apex, 1_566_000_000);
+    /// assert_eq!(result.status, Status::Indeterminate);
+    /// ```
+    pub fn validate(&self, store: &RecordStore, apex: &Name, time: u32) -> Validation {
+        let ds_type = dnssec_type(DNSSECRecordType::DS);
+        let dnskey_type = dnssec_type(DNSSECRecordType::DNSKEY);
+
+        let mut trusted_ds: Vec<DS> = self
+            .anchors
+            .iter()
+            .filter(|(name, _)| name.is_root())
+            .map(|(_, ds)| ds.clone())
+            .collect();
+        if trusted_ds.is_empty() {
+            return Validation::new(
+                Status::Indeterminate,
+                "No trust anchor for the root".to_string(),
+            );
+        }
+
+        let mut zone_keys: Vec<DNSKEY> = Vec::new();
+        let mut signer = Name::root();
+        for zone in zone_chain(apex) {
+            let zone_name = name_key(&zone);
+            if !zone.is_root() {
+                if !store.was_queried(&zone, ds_type) {
+                    return Validation::new(
+                        Status::Indeterminate,
+                        format!("DS of {} was not collected", zone_name),
+                    );
+                }
+                let ds_records = store.rrset(&zone, ds_type, &zone, ds_type);
+                if ds_records.is_empty() {
+                    // A name short of the apex without a DNSKEY is a zone cut
+                    // only where the proof of no DS shows a delegation to it
+                    let is_cut = same_name(&zone, apex)
+                        || !store
+                            .rrset(&zone, dnskey_type, &zone, dnskey_type)
+                            .is_empty();
+                    let proofs = denial_proofs(store, &zone);
+                    if proofs.is_empty() {
+                        if !is_cut {
+                            continue;
+                        }
+                        return Validation::new(
+                            Status::Indeterminate,
+                            format!("No DS for {} and no proof of its absence", zone_name),
+                        );
+                    }
+                    match verify_denial(store, &zone, &signer, &proofs, &zone_keys, time) {
+                        Ok(delegation) if is_cut || delegation => {
+                            return Validation::new(
+                                Status::Insecure,
+                                format!("No DS for {} in {}", zone_name, name_key(&signer)),
+                            )
+                        }
+                        Ok(_) => continue,
+                        Err(reason) => return Validation::new(Status::Bogus, reason),
+                    }
+                }
+                if let Err(reason) =
+                    verify_rrset(store, &zone, ds_type, &zone, &ds_records, &zone_keys, time)
+                {
+                    return Validation::new(Status::Bogus, reason);
+                }
+                trusted_ds = ds_records
+                    .iter()
+                    .filter_map(|r| match r.rdata() {
+                        RData::DNSSEC(DNSSECRData::DS(ds)) => Some(ds.clone()),
+                        _ => None,
+                    })
+                    .collect();
+            }
+
+            if !store.was_queried(&zone, dnskey_type) {
+                return Validation::new(
+                    Status::Indeterminate,
+                    format!("DNSKEY of {} was not collected", zone_name),
+                );
+            }
+            let key_records = store.rrset(&zone, dnskey_type, &zone, dnskey_type);
+            let keys: Vec<DNSKEY> = key_records
+                .iter()
+                .filter_map(|r| match r.rdata() {
+                    RData::DNSSEC(DNSSECRData::DNSKEY(key)) => Some(key.clone()),
+                    _ => None,
+                })
+                .collect();
+            if keys.is_empty() {
+                return Validation::new(
+                    Status::Bogus,
+                    format!("DS exists for {} but it has no DNSKEY", zone_name),
+                );
+            }
+            let entry_keys: Vec<DNSKEY> = keys
+                .iter()
+                .filter(|k| {
+                    trusted_ds
+                        .iter()
+                        .any(|ds| ds.covers(&zone, k).unwrap_or(false))
+                })
+                .cloned()
+                .collect();
+            if entry_keys.is_empty() {
+                return Validation::new(
+                    Status::Bogus,
+                    format!("No DNSKEY of {} matches its DS", zone_name),
+                );
+            }
+            if let Err(reason) = verify_rrset(
+                store,
+                &zone,
+                dnskey_type,
+                &zone,
+                &key_records,
+                &entry_keys,
+                time,
+            ) {
+                return Validation::new(Status::Bogus, reason);
+            }
+
+            zone_keys = keys;
+            signer = zone;
+        }
+
+        let soa = store.rrset(apex, RecordType::SOA, apex, RecordType::SOA);
+        if soa.is_empty() {
+            return Validation::new(
+                Status::Indeterminate,
+                format!("No SOA was collected for {}", name_key(apex)),
+            );
+        }
+        match verify_rrset(store, apex, RecordType::SOA, apex, &soa, &zone_keys, time) {
+            Ok(_) => Validation::new(
+                Status::Secure,
+                format!("SOA of {} validated from the trust anchor", name_key(apex)),
+            ),
+            Err(reason) => Validation::new(Status::Bogus, reason),
+        }
+    }
+}
+
+/// Verify that a record set carries at least one valid signature, within its
+/// validity period, made by one of the given keys.
+fn verify_rrset(
+    store: &RecordStore,
+    name: &Name,
+    query_type: RecordType,
+    owner: &Name,
+    records: &[Record],
+    keys: &[DNSKEY],
+    time: u32,
+) -> Result<(), String> {
+    let record_type = records[0].record_type();
+    let description = format!("{} {}", name_key(owner), record_type);
+    let sigs = store.rrsigs(name, query_type, owner, record_type);
+    if sigs.is_empty() {
+        return Err(format!("{} is not signed", description));
+    }
+
+    let mut reason = format!("No DNSKEY validates the signature of {}", description);
+    for sig in &sigs {
+        if time < sig.sig_inception() || time > sig.sig_expiration() {
+            reason = format!(
+                "Signature of {} is outside its validity period",
+                description
+            );
+            continue;
+        }
+        let candidates = keys.iter().filter(|k| {
+            k.algorithm() == sig.algorithm() && k.calculate_key_tag().ok() == Some(sig.key_tag())
+        });
+        for key in candidates {
+            if key.verify_rrsig(owner, DNSClass::IN, sig, records).is_ok() {
+                return Ok(());
+            }
+        }
+    }
+    Err(reason)
+}
+
+/// NSEC and NSEC3 records returned in the authority section alongside an
+/// empty answer to the DS query for a zone.
+fn denial_proofs<'a>(store: &'a RecordStore, zone: &Name) -> Vec<&'a Record> {
+    let ds_type = dnssec_type(DNSSECRecordType::DS);
+    store
+        .response(zone, ds_type)
+        .filter(|r| match r.record_type() {
+            RecordType::DNSSEC(DNSSECRecordType::NSEC)
+            | RecordType::DNSSEC(DNSSECRecordType::NSEC3) => true,
+            _ => false,
+        })
+        .collect()
+}
+
+/// Verify the proof that no DS exists for a zone, every NSEC or NSEC3 record
+/// of which must be signed by the parent. NSEC records are checked as
+/// described in [RFC 4035 &sect; 5.4](https://tools.ietf.org/html/rfc4035#section-5.4)
+/// and NSEC3 records as in [RFC 5155 &sect; 8.6](https://tools.ietf.org/html/rfc5155#section-8.6).
+/// Returns whether the proof shows a delegation to the zone - a matching
+/// record listing NS, or an NSEC3 with the Opt-Out flag covering it - rather
+/// than a name within the parent zone, or no such name at all.
+///
+/// # Arguments
+/// * `store` - Records collected for every name in the chain
+/// * `zone` - Zone whose DS is denied
+/// * `parent` - Zone the proof must come from, the last along the chain
+/// * `proofs` - NSEC and NSEC3 records returned by the DS query
+/// * `keys` - DNSKEYs of the parent
+/// * `time` - Time against which signature validity periods are checked
+fn verify_denial(
+    store: &RecordStore,
+    zone: &Name,
+    parent: &Name,
+    proofs: &[&Record],
+    keys: &[DNSKEY],
+    time: u32,
+) -> Result<bool, String> {
+    let ds_type = dnssec_type(DNSSECRecordType::DS);
+    for proof in proofs {
+        let rrset = store.rrset(zone, ds_type, proof.name(), proof.record_type());
+        verify_rrset(store, zone, ds_type, proof.name(), &rrset, keys, time)?;
+    }
+
+    let nsecs: Vec<(&Name, &NSEC)> = proofs
+        .iter()
+        .filter_map(|r| match r.rdata() {
+            RData::DNSSEC(DNSSECRData::NSEC(nsec)) => Some((r.name(), nsec)),
+            _ => None,
+        })
+        .collect();
+    if !nsecs.is_empty() {
+        return verify_nsec_denial(zone, &nsecs);
+    }
+    let nsec3s: Vec<(&Name, &NSEC3)> = proofs
+        .iter()
+        .filter_map(|r| match r.rdata() {
+            RData::DNSSEC(DNSSECRData::NSEC3(nsec3)) => Some((r.name(), nsec3)),
+            _ => None,
+        })
+        .collect();
+    verify_nsec3_denial(zone, parent, &nsec3s)
+}
+
+/// An NSEC owned by the zone must come from the parent side of the
+/// delegation and not list DS in its type bitmap, otherwise an NSEC must
+/// cover the zone - proving that no such name exists at all.
+fn verify_nsec_denial(zone: &Name, nsecs: &[(&Name, &NSEC)]) -> Result<bool, String> {
+    let zone_name = name_key(zone);
+    for (owner, nsec) in nsecs {
+        if same_name(owner, zone) {
+            return denies_ds(&format!("NSEC of {}", zone_name), nsec.type_bit_maps());
+        }
+    }
+    let key = canonical_key(zone);
+    if nsecs.iter().any(|(owner, nsec)| {
+        covers(
+            &canonical_key(owner),
+            &canonical_key(nsec.next_domain_name()),
+            &key,
+        )
+    }) {
+        return Ok(false);
+    }
+    Err(format!("No NSEC matches or covers {}", zone_name))
+}
+
+/// An NSEC3 matching the zone must come from the parent side of the
+/// delegation and not list DS in its type bitmap. Otherwise the closest
+/// encloser of the zone must be proven by a matching NSEC3, and the next
+/// closer name covered by one with the Opt-Out flag set, i.e. the zone is an
+/// unsigned delegation skipped by the chain.
+fn verify_nsec3_denial(
+    zone: &Name,
+    parent: &Name,
+    nsec3s: &[(&Name, &NSEC3)],
+) -> Result<bool, String> {
+    let zone_name = name_key(zone);
+    // Hashed owner and next owner of each NSEC3 of the parent zone
+    let chain: Vec<(String, String, &NSEC3)> = nsec3s
+        .iter()
+        .filter(|(owner, _)| same_name(&owner.base_name(), parent))
+        .filter_map(|(owner, nsec3)| {
+            let label = owner.iter().next()?;
+            Some((
+                String::from_utf8_lossy(label).to_ascii_lowercase(),
+                base32hex(nsec3.next_hashed_owner_name()),
+                *nsec3,
+            ))
+        })
+        .collect();
+    let params = match chain.first() {
+        Some((_, _, nsec3)) => *nsec3,
+        None => return Err(format!("No NSEC or NSEC3 proves {} has no DS", zone_name)),
+    };
+    let hash = |name: &Name| -> Result<String, String> {
+        params
+            .hash_algorithm()
+            .hash(params.salt(), name, params.iterations())
+            .map(|digest| base32hex(digest.as_ref()))
+            .map_err(|e| format!("Unable to hash {} - {}", name_key(name), e))
+    };
+    let matching = |hashed: &str| chain.iter().find(|(owner, _, _)| owner == hashed);
+    let covering = |hashed: &String| {
+        chain
+            .iter()
+            .find(|(owner, next, _)| covers(owner, next, hashed))
+    };
+
+    if let Some((_, _, nsec3)) = matching(&hash(zone)?) {
+        return denies_ds(&format!("NSEC3 of {}", zone_name), nsec3.type_bit_maps());
+    }
+
+    let mut next_closer = zone.clone();
+    while !same_name(&next_closer, parent) && !next_closer.is_root() {
+        let encloser = next_closer.base_name();
+        if matching(&hash(&encloser)?).is_some() {
+            return match covering(&hash(&next_closer)?) {
+                Some((_, _, nsec3)) if nsec3.opt_out() => Ok(true),
+                Some(_) => Err(format!(
+                    "NSEC3 covering {} does not have opt-out set",
+                    name_key(&next_closer)
+                )),
+                None => Err(format!("No NSEC3 covers {}", name_key(&next_closer))),
+            };
+        }
+        next_closer = encloser;
+    }
+    Err(format!(
+        "No NSEC3 proves a closest encloser of {}",
+        zone_name
+    ))
+}
+
+/// The type bitmap of a record at the delegation must not list DS, nor SOA
+/// which would place it at the apex of the child zone rather than the parent.
+/// Returns whether it lists NS, i.e. the name is delegated.
+fn denies_ds(description: &str, types: &[RecordType]) -> Result<bool, String> {
+    if types.contains(&dnssec_type(DNSSECRecordType::DS)) {
+        return Err(format!("{} asserts that a DS exists", description));
+    }
+    if types.contains(&RecordType::SOA) {
+        return Err(format!("{} is from the child zone", description));
+    }
+    Ok(types.contains(&RecordType::NS))
+}
+
+/// Whether `name` falls strictly between `owner` and `next`, the last record
+/// of a chain wrapping around to the first.
+fn covers<T: Ord>(owner: &T, next: &T, name: &T) -> bool {
+    if owner < next {
+        owner < name && name < next
+    } else {
+        owner < name || name < next
+    }
+}
+
+/// A key ordering names canonically, as described in
+/// [RFC 4034 &sect; 6.1](https://tools.ietf.org/html/rfc4034#section-6.1) -
+/// lower case labels compared from the root down.
+fn canonical_key(name: &Name) -> Vec<Vec<u8>> {
+    let mut labels: Vec<Vec<u8>> = name.iter().map(|l| l.to_ascii_lowercase()).collect();
+    labels.reverse();
+    labels
+}
+
+/// Encode as Base 32 with the extended hex alphabet and no padding, as used
+/// by the owner names of NSEC3 records, which preserves the hashes' order.
+fn base32hex(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"0123456789abcdefghijklmnopqrstuv";
+    let mut encoded = String::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for byte in bytes {
+        buffer = ((buffer << 8) | u32::from(*byte)) & 0xFFFF;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(ALPHABET[((buffer >> bits) & 0x1F) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        encoded.push(ALPHABET[((buffer << (5 - bits)) & 0x1F) as usize] as char);
+    }
+    encoded
+}
@@ -0,0 +1,190 @@
+use super::dns::random_u64;
+use failure::Error;
+use std::fmt;
+use std::io::{Read, Write};
+use std::net::{IpAddr, SocketAddr, TcpStream, UdpSocket};
+use std::str::FromStr;
+use std::time::Duration;
+use trust_dns::op::{Message, MessageType, OpCode, Query};
+use trust_dns::rr::{Name, RecordType};
+use trust_dns_proto::rr::rdata::opt::{EdnsCode, EdnsOption};
+
+/// EDNS(0) UDP payload size advertised in queries, large enough for most
+/// DNSKEY responses to avoid falling back to TCP.
+const MAX_PAYLOAD: u16 = 4096;
+
+/// Time to wait for a response before giving up on a server.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Build a query message with EDNS(0) present, which the caller may alter
+/// further (e.g. setting the DO bit) before passing it to
+/// [exchange](fn.exchange.html). The `SyncClient` gives no control over the
+/// EDNS options sent, hence the need to construct messages ourselves.
+///
+/// # Arguments
+/// * `name` - Name to query
+/// * `query_type` - The DNS RecordType to query
+/// * `recursion_desired` - Set true when querying a resolver
+///
+/// ```
+/// use damp::exchange::build_query;
+/// use trust_dns::rr::{Name, RecordType};
+///
+/// let name = Name::from_ascii("example.com").unwrap();
+/// let mut message = build_query(&name, RecordType::SOA, true);
+/// message.edns_mut().set_dnssec_ok(true);
+/// assert!(message.recursion_desired());
+/// assert!(message.edns().unwrap().dnssec_ok());
+/// ```
+pub fn build_query(name: &Name, query_type: RecordType, recursion_desired: bool) -> Message {
+    let mut message = Message::new();
+    message
+        .set_id(random_u64() as u16)
+        .set_message_type(MessageType::Query)
+        .set_op_code(OpCode::Query)
+        .set_recursion_desired(recursion_desired)
+        .add_query(Query::query(name.clone(), query_type));
+    message.edns_mut().set_max_payload(MAX_PAYLOAD);
+    message
+}
+
+/// Send a query message to a server over UDP and wait for its response,
+/// retrying over TCP should the response be truncated.
+///
+/// # Arguments
+/// * `server` - Socket address of the resolver or name server
+/// * `message` - The query, see [build_query](fn.build_query.html)
+pub fn exchange(server: SocketAddr, message: &Message) -> Result<Message, Error> {
+    let request = message.to_vec()?;
+    let bind_addr = match server {
+        SocketAddr::V4(_) => "0.0.0.0:0",
+        SocketAddr::V6(_) => "[::]:0",
+    };
+    let socket = UdpSocket::bind(bind_addr)?;
+    socket.set_read_timeout(Some(TIMEOUT))?;
+    socket.send_to(&request, server)?;
+
+    let mut buffer = vec![0u8; u16::max_value() as usize];
+    loop {
+        let (len, from) = socket.recv_from(&mut buffer)?;
+        // Discard anything that isn't the answer to our query
+        if from != server {
+            continue;
+        }
+        let response = match Message::from_vec(&buffer[..len]) {
+            Ok(r) => r,
+            Err(_) => continue,
+        };
+        if response.id() != message.id() {
+            continue;
+        }
+        return match response.truncated() {
+            true => exchange_tcp(server, &request),
+            false => Ok(response),
+        };
+    }
+}
+
+/// Send an encoded query over TCP, prefixed by its length as per
+/// [RFC 1035 &sect; 4.2.2](https://tools.ietf.org/html/rfc1035#section-4.2.2).
+fn exchange_tcp(server: SocketAddr, request: &[u8]) -> Result<Message, Error> {
+    let mut stream = TcpStream::connect_timeout(&server, TIMEOUT)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.write_all(&(request.len() as u16).to_be_bytes())?;
+    stream.write_all(request)?;
+
+    let mut length = [0u8; 2];
+    stream.read_exact(&mut length)?;
+    let mut response = vec![0u8; u16::from_be_bytes(length) as usize];
+    stream.read_exact(&mut response)?;
+    Ok(Message::from_vec(&response)?)
+}
+
+/// An EDNS Client Subnet, as described in
+/// [RFC 7871](https://tools.ietf.org/html/rfc7871), attached to queries so
+/// that a resolver answers as though the query came from within the subnet.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ClientSubnet {
+    pub address: IpAddr,
+    pub source_prefix: u8,
+}
+
+impl ClientSubnet {
+    /// Encode as an EDNS option, the address truncated to the prefix length
+    /// as required by [RFC 7871 &sect; 6](https://tools.ietf.org/html/rfc7871#section-6).
+    ///
+    /// ```
+    /// use damp::exchange::ClientSubnet;
+    /// use trust_dns_proto::rr::rdata::opt::EdnsOption;
+    ///
+    /// let subnet: ClientSubnet = "192.0.2.0/24".parse().unwrap();
+    /// assert_eq!(
+    ///     subnet.to_option(),
+    ///     EdnsOption::Unknown(8, vec![0, 1, 24, 0, 192, 0, 2])
+    /// );
+    /// ```
+    pub fn to_option(&self) -> EdnsOption {
+        let (family, octets): (u16, Vec<u8>) = match self.address {
+            IpAddr::V4(ip) => (1, ip.octets().to_vec()),
+            IpAddr::V6(ip) => (2, ip.octets().to_vec()),
+        };
+        let length = (self.source_prefix as usize + 7) / 8;
+        let mut address: Vec<u8> = octets.into_iter().take(length).collect();
+        // Zero any bits beyond the prefix in the final octet
+        if self.source_prefix % 8 != 0 {
+            if let Some(last) = address.last_mut() {
+                *last &= 0xff << (8 - self.source_prefix % 8);
+            }
+        }
+
+        let mut data = family.to_be_bytes().to_vec();
+        data.push(self.source_prefix);
+        data.push(0);
+        data.extend(address);
+        EdnsOption::Unknown(EdnsCode::Subnet.into(), data)
+    }
+}
+
+impl FromStr for ClientSubnet {
+    type Err = Error;
+
+    /// Parse a subnet in CIDR notation, e.g. `192.0.2.0/24` or `2001:db8::/32`
+    fn from_str(s: &str) -> Result<ClientSubnet, Error> {
+        let mut parts = s.splitn(2, '/');
+        let address: IpAddr = parts.next().unwrap_or("").parse()?;
+        let max_prefix = match address {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        let source_prefix: u8 = match parts.next() {
+            Some(p) => p.parse()?,
+            None => max_prefix,
+        };
+        if source_prefix > max_prefix {
+            return Err(failure::format_err!("Invalid prefix length in '{}'", s));
+        }
+        Ok(ClientSubnet {
+            address,
+            source_prefix,
+        })
+    }
+}
+
+impl fmt::Display for ClientSubnet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.address, self.source_prefix)
+    }
+}
+
+/// The scope prefix length returned by the resolver in its EDNS Client Subnet
+/// option, denoting how much of the subnet the answer is specific to. Absent
+/// should the resolver not support, or not have used, the client subnet.
+///
+/// # Arguments
+/// * `response` - Response to a query sent with a client subnet
+pub fn scope_prefix(response: &Message) -> Option<u8> {
+    match response.edns().and_then(|e| e.option(EdnsCode::Subnet)) {
+        Some(EdnsOption::Unknown(_, data)) if data.len() >= 4 => Some(data[3]),
+        _ => None,
+    }
+}
@@ -0,0 +1,162 @@
+use failure::Error;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+
+/// How strongly the evidence gathered for a domain supports the hosting
+/// provider assigned to it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Confidence {
+    /// Both the CNAME target and the ASN of the answer point to the provider
+    High,
+    /// Only the CNAME target matched a known provider
+    Medium,
+    /// Only the ASN of the answer matched a known provider
+    Low,
+}
+
+impl Confidence {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Confidence::High => "high",
+            Confidence::Medium => "medium",
+            Confidence::Low => "low",
+        }
+    }
+}
+
+/// A database of fingerprints used to attribute hosting of a name to a CDN or
+/// hosting provider. Fingerprints come in two kinds - a suffix that a CNAME
+/// target ends in (e.g. `cloudfront.net`) or an Autonomous System Number that
+/// an address in the answer is announced from.
+///
+/// The database is a CSV file with a header of `kind,pattern,provider`, where
+/// `kind` is either `cname` or `asn`:
+///
+/// ```text
+/// kind,pattern,provider
+/// cname,cloudfront.net,Amazon
+/// asn,16509,Amazon
+/// ```
+pub struct Fingerprints {
+    suffixes: Vec<(String, String)>,
+    asns: HashMap<i32, String>,
+}
+
+impl Fingerprints {
+    /// Load the fingerprint database from a CSV file on disk.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the fingerprint CSV file
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Fingerprints, Error> {
+        let file = std::fs::File::open(path)?;
+        Fingerprints::from_reader(file)
+    }
+
+    /// Load the fingerprint database from anything implementing `Read`.
+    ///
+    /// ```
+    /// use damp::fingerprint::Fingerprints;
+    ///
+    /// let csv = "kind,pattern,provider\ncname,cloudfront.net,Amazon\nasn,16509,Amazon\n";
+    /// let fingerprints = Fingerprints::from_reader(csv.as_bytes()).unwrap();
+    /// assert_eq!(fingerprints.match_cname("d111111abcdef8.cloudfront.net."), Some("Amazon"));
+    /// assert_eq!(fingerprints.match_asn(16509), Some("Amazon"));
+    /// ```
+    pub fn from_reader<R: Read>(reader: R) -> Result<Fingerprints, Error> {
+        let mut suffixes: Vec<(String, String)> = Vec::new();
+        let mut asns: HashMap<i32, String> = HashMap::new();
+        let mut csv_reader = csv::ReaderBuilder::new()
+            .has_headers(true)
+            .comment(Some(b'#'))
+            .from_reader(reader);
+
+        for line in csv_reader.records() {
+            // For record.get(), offset 0 is kind, 1 is pattern, 2 is provider
+            let record = line?;
+            let kind = record.get(0).unwrap_or("").trim();
+            let pattern = record.get(1).unwrap_or("").trim();
+            let provider = record.get(2).unwrap_or("").trim().to_string();
+            match kind {
+                "cname" => suffixes.push((normalise_name(pattern), provider)),
+                "asn" => {
+                    asns.insert(pattern.parse()?, provider);
+                }
+                _ => return Err(failure::format_err!("Unknown fingerprint kind '{}'", kind)),
+            }
+        }
+
+        // Sort longest suffix first, so that the most specific match wins
+        suffixes.sort_by(|a, b| b.0.len().cmp(&a.0.len()));
+
+        Ok(Fingerprints { suffixes, asns })
+    }
+
+    /// Find the provider whose suffix the CNAME target falls under. Matching
+    /// is case-insensitive and only occurs on label boundaries, thus
+    /// `cloudfront.net` matches `abc.cloudfront.net` but not
+    /// `notcloudfront.net`.
+    ///
+    /// # Arguments
+    /// * `target` - The CNAME target, with or without the trailing dot
+    ///
+    /// ```
+    /// use damp::fingerprint::Fingerprints;
+    ///
+    /// let csv = "kind,pattern,provider\ncname,akamaiedge.net,Akamai\n";
+    /// let fingerprints = Fingerprints::from_reader(csv.as_bytes()).unwrap();
+    /// assert_eq!(fingerprints.match_cname("e1.a.AKAMAIEDGE.net"), Some("Akamai"));
+    /// assert_eq!(fingerprints.match_cname("notakamaiedge.net"), None);
+    /// ```
+    pub fn match_cname(&self, target: &str) -> Option<&str> {
+        let target = normalise_name(target);
+        self.suffixes
+            .iter()
+            .find(|(suffix, _)| {
+                target == *suffix || target.ends_with(&format!(".{}", suffix))
+            })
+            .map(|(_, provider)| provider.as_str())
+    }
+
+    /// Find the provider operating the given Autonomous System Number.
+    ///
+    /// # Arguments
+    /// * `asn` - Autonomous System Number
+    pub fn match_asn(&self, asn: i32) -> Option<&str> {
+        self.asns.get(&asn).map(String::as_str)
+    }
+}
+
+/// Combine the provider matched against a CNAME target with that matched
+/// against the ASN of the answer's addresses, producing the provider the name
+/// is hosted by and how confident we are of it. Where the two disagree, the
+/// CNAME target is preferred as it is chosen explicitly by the zone operator.
+///
+/// ```
+/// use damp::fingerprint::{hosted_by, Confidence};
+///
+/// assert_eq!(
+///     hosted_by(Some("Fastly"), Some("Fastly")),
+///     Some(("Fastly".to_string(), Confidence::High))
+/// );
+/// assert_eq!(
+///     hosted_by(None, Some("Amazon")),
+///     Some(("Amazon".to_string(), Confidence::Low))
+/// );
+/// assert_eq!(hosted_by(None, None), None);
+/// ```
+pub fn hosted_by(
+    cname_provider: Option<&str>,
+    asn_provider: Option<&str>,
+) -> Option<(String, Confidence)> {
+    match (cname_provider, asn_provider) {
+        (Some(c), Some(a)) if c == a => Some((c.to_string(), Confidence::High)),
+        (Some(c), _) => Some((c.to_string(), Confidence::Medium)),
+        (None, Some(a)) => Some((a.to_string(), Confidence::Low)),
+        (None, None) => None,
+    }
+}
+
+fn normalise_name(name: &str) -> String {
+    name.trim_end_matches('.').to_ascii_lowercase()
+}
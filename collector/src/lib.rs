@@ -5,9 +5,24 @@ use chrono::prelude::Utc;
 use std::{thread,time};
 use std::time::{Instant,SystemTime};
 
+pub mod apex;
+pub mod as2org;
+pub mod asn;
+pub mod authority;
+pub mod cloud;
+pub mod compress;
 pub mod dns;
+pub mod dnssec;
+pub mod exchange;
+pub mod fingerprint;
+pub mod manipulation;
+pub mod mmdb;
 pub mod model;
+pub mod ranking;
+pub mod rpki;
 pub mod schema;
+pub mod special;
+pub mod stability;
 
 static SLEEP_PERIOD: &'static time::Duration = &time::Duration::from_millis(100);
 
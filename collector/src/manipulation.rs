@@ -0,0 +1,185 @@
+use crate::authority::same_name;
+use std::net::IpAddr;
+use trust_dns::op::{Message, ResponseCode};
+use trust_dns::rr::{Name, RData};
+
+/// Verdict on whether a resolver's response has been tampered with, judged
+/// against the answers of the zone's authoritative servers. A response is
+/// only considered manipulated when it differs from every authoritative
+/// answer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Verdict {
+    /// The response agrees with at least one authoritative answer.
+    Consistent,
+    /// The authoritative servers answered `NXDOMAIN`, yet the resolver
+    /// answered with records, e.g. redirecting to a search or ad page.
+    NxdomainRewritten,
+    /// The response points at an address that is never served publicly, such
+    /// as `0.0.0.0` or `127.0.0.1`, commonly used to block domains.
+    Sinkhole,
+    /// The response points at a private address, e.g. in `10.0.0.0/8`, which
+    /// may be a block page on the resolver's network or simply a name only
+    /// resolvable internally.
+    PrivateAnswer,
+    /// The authoritative servers answered, but the resolver returned an error
+    /// or no records, indicative of filtering.
+    Filtered,
+    /// The resolver answered with records other than those served by the
+    /// authoritative servers.
+    UnexpectedAnswer,
+    /// No authoritative server gave an authoritative answer to compare with.
+    Unverified,
+}
+
+impl Verdict {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Verdict::Consistent => "consistent",
+            Verdict::NxdomainRewritten => "nxdomain_rewritten",
+            Verdict::Sinkhole => "sinkhole",
+            Verdict::PrivateAnswer => "private_answer",
+            Verdict::Filtered => "filtered",
+            Verdict::UnexpectedAnswer => "unexpected_answer",
+            Verdict::Unverified => "unverified",
+        }
+    }
+}
+
+/// Whether the address is one that resolvers hand out in place of the real
+/// answer to block a domain, being unroutable or pointing back at the client.
+///
+/// ```
+/// use damp::manipulation::is_sinkhole;
+///
+/// assert!(is_sinkhole(&"0.0.0.0".parse().unwrap()));
+/// assert!(is_sinkhole(&"127.0.0.1".parse().unwrap()));
+/// assert!(is_sinkhole(&"::".parse().unwrap()));
+/// assert!(!is_sinkhole(&"192.0.2.1".parse().unwrap()));
+/// assert!(!is_sinkhole(&"10.0.0.1".parse().unwrap()));
+/// ```
+pub fn is_sinkhole(address: &IpAddr) -> bool {
+    match address {
+        IpAddr::V4(ip) => ip.is_unspecified() || ip.is_loopback(),
+        IpAddr::V6(ip) => ip.is_unspecified() || ip.is_loopback(),
+    }
+}
+
+/// Whether the address is private, either in the ranges of
+/// [RFC 1918](https://tools.ietf.org/html/rfc1918) or an IPv6 unique local
+/// address in `fc00::/7`.
+///
+/// ```
+/// use damp::manipulation::is_private;
+///
+/// assert!(is_private(&"192.168.1.1".parse().unwrap()));
+/// assert!(is_private(&"fd00::1".parse().unwrap()));
+/// assert!(!is_private(&"192.0.2.1".parse().unwrap()));
+/// ```
+pub fn is_private(address: &IpAddr) -> bool {
+    match address {
+        IpAddr::V4(ip) => ip.is_private(),
+        IpAddr::V6(ip) => (ip.segments()[0] & 0xfe00) == 0xfc00,
+    }
+}
+
+/// Assess the response of a resolver against the responses of the zone's
+/// authoritative servers for the same query, returning the verdict along with
+/// a short description of the difference.
+///
+/// Only the records owned by the queried name are compared - the target of a
+/// CNAME will frequently be out of the zone, and so is not known to its
+/// authoritative servers. Responses that are not authoritative, e.g. a
+/// referral, are disregarded.
+///
+/// # Arguments
+/// * `name` - Name queried
+/// * `resolver` - Response of the resolver
+/// * `authoritative` - Responses of each authoritative server
+pub fn assess(name: &Name, resolver: &Message, authoritative: &[Message]) -> (Verdict, String) {
+    let answers: Vec<&Message> = authoritative.iter().filter(|m| m.authoritative()).collect();
+    if answers.is_empty() {
+        return (
+            Verdict::Unverified,
+            "no authoritative answer received".to_string(),
+        );
+    }
+
+    let resolver_code = resolver.response_code();
+    let resolver_data = owned_data(name, resolver);
+    let matches = answers.iter().any(|a| {
+        let data = owned_data(name, a);
+        a.response_code() == resolver_code
+            && ((data.is_empty() && resolver_data.is_empty())
+                || data.iter().any(|d| resolver_data.contains(d)))
+    });
+    if matches {
+        return (Verdict::Consistent, String::new());
+    }
+
+    let addresses: Vec<IpAddr> = resolver
+        .answers()
+        .iter()
+        .filter_map(|r| r.rdata().to_ip_addr())
+        .collect();
+    let sinkholes: Vec<String> = addresses
+        .iter()
+        .filter(|ip| is_sinkhole(ip))
+        .map(|ip| ip.to_string())
+        .collect();
+    let private: Vec<String> = addresses
+        .iter()
+        .filter(|ip| is_private(ip))
+        .map(|ip| ip.to_string())
+        .collect();
+    let codes = answers
+        .iter()
+        .map(|a| a.response_code().to_string())
+        .collect::<Vec<String>>()
+        .join(",");
+
+    if !sinkholes.is_empty() {
+        (
+            Verdict::Sinkhole,
+            format!("resolver answered {}", sinkholes.join(",")),
+        )
+    } else if !private.is_empty() {
+        (
+            Verdict::PrivateAnswer,
+            format!("resolver answered {}", private.join(",")),
+        )
+    } else if answers
+        .iter()
+        .all(|a| a.response_code() == ResponseCode::NXDomain)
+        && !resolver.answers().is_empty()
+    {
+        (
+            Verdict::NxdomainRewritten,
+            format!(
+                "authoritative {}, resolver {} with {} answers",
+                codes,
+                resolver_code,
+                resolver.answers().len()
+            ),
+        )
+    } else if resolver_code != ResponseCode::NoError || resolver_data.is_empty() {
+        (
+            Verdict::Filtered,
+            format!("authoritative {}, resolver {}", codes, resolver_code),
+        )
+    } else {
+        (
+            Verdict::UnexpectedAnswer,
+            format!("{} records unlike any authoritative", resolver_data.len()),
+        )
+    }
+}
+
+/// The data of records in the answer section owned by the name.
+fn owned_data<'a>(name: &Name, message: &'a Message) -> Vec<&'a RData> {
+    message
+        .answers()
+        .iter()
+        .filter(|r| same_name(r.name(), name))
+        .map(|r| r.rdata())
+        .collect()
+}
@@ -0,0 +1,199 @@
+use crate::asn::cidr;
+use crate::compress;
+use chrono::{TimeZone, Utc};
+use failure::Error;
+use maxminddb::geoip2;
+use maxminddb::{Metadata, Reader};
+use std::net::IpAddr;
+use std::path::Path;
+
+/// Open a MaxMind DB held in memory, as `Reader::open_readfile` does, from a
+/// file that may be compressed or within a tarball as MaxMind distribute it,
+/// e.g. `GeoLite2-ASN.tar.gz`.
+///
+/// # Arguments
+/// * `path` - Path to the MaxMind DB
+pub fn open_reader<P: AsRef<Path>>(path: P) -> Result<Reader<Vec<u8>>, Error> {
+    let buf = compress::read_member(path, ".mmdb")?;
+    Ok(Reader::from_source(buf)?)
+}
+
+/// Open a MaxMind DB and its search tree as `open_reader` does, reading and
+/// decompressing the file only once.
+///
+/// ```
+/// use damp::mmdb;
+///
+/// let (reader, tree) = mmdb::open("tests/fixtures/GeoLite2-ASN-Test.mmdb").unwrap();
+/// assert_eq!(reader.metadata.database_type, "GeoLite2-ASN");
+/// assert_eq!(tree.prefix("1.130.5.6".parse().unwrap()), Some("1.128.0.0/11".to_string()));
+/// ```
+pub fn open<P: AsRef<Path>>(path: P) -> Result<(Reader<Vec<u8>>, SearchTree), Error> {
+    let buf = compress::read_member(path, ".mmdb")?;
+    let search_tree = {
+        let reader = Reader::from_source(&buf[..])?;
+        SearchTree::from_bytes(&buf, &reader.metadata)?
+    };
+    Ok((Reader::from_source(buf)?, search_tree))
+}
+
+/// The binary search tree at the start of a MaxMind DB, walked bit by bit of
+/// an address to find the network it was matched in. The `maxminddb` crate
+/// only returns the data of the network, so the tree is walked separately to
+/// recover the prefix.
+///
+/// See Also: [MaxMind DB File Format Specification](https://maxmind.github.io/MaxMind-DB/)
+pub struct SearchTree {
+    buf: Vec<u8>,
+    node_count: usize,
+    record_size: usize,
+    ip_version: u16,
+}
+
+impl SearchTree {
+    /// Read the search tree of the database at the path.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the MaxMind DB
+    /// * `metadata` - Metadata of the same database, as read by
+    ///   `maxminddb::Reader`
+    pub fn from_path<P: AsRef<Path>>(path: P, metadata: &Metadata) -> Result<SearchTree, Error> {
+        let buf = compress::read_member(path, ".mmdb")?;
+        SearchTree::from_bytes(&buf, metadata)
+    }
+
+    /// Copy the search tree from the start of a database held in memory.
+    ///
+    /// # Arguments
+    /// * `buf` - The MaxMind DB
+    /// * `metadata` - Metadata of the same database
+    pub fn from_bytes(buf: &[u8], metadata: &Metadata) -> Result<SearchTree, Error> {
+        let record_size = metadata.record_size as usize;
+        match record_size {
+            24 | 28 | 32 => {}
+            s => return Err(failure::format_err!("Unsupported record size {}", s)),
+        }
+        let node_count = metadata.node_count as usize;
+        let tree_size = node_count * record_size / 4;
+        if buf.len() < tree_size {
+            return Err(failure::format_err!("Search tree truncated"));
+        }
+        Ok(SearchTree {
+            buf: buf[..tree_size].to_vec(),
+            node_count,
+            record_size,
+            ip_version: metadata.ip_version,
+        })
+    }
+
+    /// The network in which the address was matched, in CIDR notation, or
+    /// `None` where the address is not in the database.
+    ///
+    /// ```
+    /// use damp::mmdb::SearchTree;
+    /// use maxminddb::Reader;
+    ///
+    /// let path = "tests/fixtures/GeoLite2-ASN-Test.mmdb";
+    /// let reader = Reader::open_readfile(path).unwrap();
+    /// let tree = SearchTree::from_path(path, &reader.metadata).unwrap();
+    /// let prefix = tree.prefix("1.130.5.6".parse().unwrap());
+    /// assert_eq!(prefix, Some("1.128.0.0/11".to_string()));
+    /// assert_eq!(tree.prefix("8.8.8.8".parse().unwrap()), None);
+    /// ```
+    pub fn prefix(&self, address: IpAddr) -> Option<String> {
+        let (bits, value): (usize, u128) = match address {
+            IpAddr::V4(ip) => (32, u128::from(u32::from(ip))),
+            IpAddr::V6(ip) => {
+                if self.ip_version == 4 {
+                    return None;
+                }
+                (128, u128::from(ip))
+            }
+        };
+
+        // IPv4 addresses are found under ::/96 of an IPv6 tree
+        let mut node = 0;
+        if bits == 32 && self.ip_version == 6 {
+            for _ in 0..96 {
+                if node >= self.node_count {
+                    break;
+                }
+                node = self.record(node, false);
+            }
+        }
+
+        let mut depth = 0;
+        while depth < bits && node < self.node_count {
+            let bit = (value >> (bits - 1 - depth)) & 1 == 1;
+            node = self.record(node, bit);
+            depth += 1;
+        }
+
+        // A record equal to the node count denotes no data
+        if node <= self.node_count {
+            return None;
+        }
+
+        Some(cidr(address, depth))
+    }
+
+    /// Read the left or right record of a node.
+    fn record(&self, node: usize, right: bool) -> usize {
+        let size = self.record_size * 2 / 8;
+        let b = &self.buf[node * size..(node + 1) * size];
+        let be = |bytes: &[u8]| bytes.iter().fold(0, |acc, b| (acc << 8) | *b as usize);
+        match (self.record_size, right) {
+            (24, false) => be(&b[0..3]),
+            (24, true) => be(&b[3..6]),
+            (28, false) => ((b[3] as usize & 0xF0) << 20) | be(&b[0..3]),
+            (28, true) => ((b[3] as usize & 0x0F) << 24) | be(&b[4..7]),
+            (_, false) => be(&b[0..4]),
+            (_, true) => be(&b[4..8]),
+        }
+    }
+}
+
+/// Date the database was built, e.g. "2019-11-19", as given in its metadata.
+///
+/// ```
+/// use damp::mmdb::build_date;
+/// use maxminddb::Reader;
+///
+/// let reader = Reader::open_readfile("tests/fixtures/GeoLite2-ASN-Test.mmdb").unwrap();
+/// assert_eq!(build_date(&reader.metadata).len(), 10);
+/// ```
+pub fn build_date(metadata: &Metadata) -> String {
+    Utc.timestamp(metadata.build_epoch as i64, 0)
+        .format("%Y-%m-%d")
+        .to_string()
+}
+
+/// The location of an address, as found in a GeoLite2 Country or City
+/// database. A Country database only provides the country.
+#[derive(Debug, Default, PartialEq)]
+pub struct Location {
+    /// ISO 3166-1 code of the country, e.g. "GB"
+    pub country: Option<String>,
+    /// English name of the city
+    pub city: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+impl From<geoip2::City> for Location {
+    fn from(city: geoip2::City) -> Location {
+        let (latitude, longitude) = match city.location {
+            Some(l) => (l.latitude, l.longitude),
+            None => (None, None),
+        };
+        Location {
+            country: city.country.and_then(|c| c.iso_code),
+            city: city
+                .city
+                .and_then(|c| c.names)
+                .and_then(|mut n| n.remove("en")),
+            latitude,
+            longitude,
+        }
+    }
+}
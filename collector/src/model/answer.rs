@@ -0,0 +1,67 @@
+use super::super::schema::{answer_sample, answer_stability};
+
+#[derive(Queryable)]
+pub struct AnswerSample {
+    pub rowid: i64,
+    pub domain: i64,
+    pub is_www: bool,
+    pub query_type: String,
+    pub resolver: String,
+    pub sample: i32,
+    pub response_code: i32,
+    pub addresses: Option<String>,
+    pub ttl: Option<i32>,
+    pub query_time: i64,
+}
+
+#[derive(Insertable)]
+#[table_name = "answer_sample"]
+pub struct NewAnswerSample<'a> {
+    pub domain: &'a i64,
+    pub is_www: &'a bool,
+    pub query_type: &'a str,
+    pub resolver: &'a str,
+    pub sample: &'a i32,
+    pub response_code: &'a i32,
+    pub addresses: Option<&'a str>,
+    pub ttl: Option<&'a i32>,
+    pub query_time: &'a i64,
+}
+
+#[derive(Queryable)]
+pub struct AnswerStability {
+    pub rowid: i64,
+    pub domain: i64,
+    pub is_www: bool,
+    pub query_type: String,
+    pub resolver: String,
+    pub samples: i32,
+    pub distinct_sets: i32,
+    pub distinct_addresses: i32,
+    pub max_set_size: i32,
+    pub reordered: bool,
+    pub ttl_min: Option<i32>,
+    pub ttl_max: Option<i32>,
+    pub ttl_decreasing: bool,
+    pub balancing: String,
+    pub analysis_time: i64,
+}
+
+#[derive(Insertable)]
+#[table_name = "answer_stability"]
+pub struct NewAnswerStability<'a> {
+    pub domain: &'a i64,
+    pub is_www: &'a bool,
+    pub query_type: &'a str,
+    pub resolver: &'a str,
+    pub samples: &'a i32,
+    pub distinct_sets: &'a i32,
+    pub distinct_addresses: &'a i32,
+    pub max_set_size: &'a i32,
+    pub reordered: &'a bool,
+    pub ttl_min: Option<&'a i32>,
+    pub ttl_max: Option<&'a i32>,
+    pub ttl_decreasing: &'a bool,
+    pub balancing: &'a str,
+    pub analysis_time: &'a i64,
+}
@@ -0,0 +1,23 @@
+use super::super::schema::apex_alias;
+
+#[derive(Queryable)]
+pub struct ApexAlias {
+    pub rowid: i64,
+    pub domain: i64,
+    pub cname_target: String,
+    pub classification: String,
+    pub shared_addresses: i32,
+    pub shared_asns: i32,
+    pub query_time: i64,
+}
+
+#[derive(Insertable)]
+#[table_name = "apex_alias"]
+pub struct NewApexAlias<'a> {
+    pub domain: &'a i64,
+    pub cname_target: &'a str,
+    pub classification: &'a str,
+    pub shared_addresses: &'a i32,
+    pub shared_asns: &'a i32,
+    pub query_time: &'a i64,
+}
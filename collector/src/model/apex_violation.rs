@@ -0,0 +1,25 @@
+use super::super::schema::apex_violation;
+
+#[derive(Queryable)]
+pub struct ApexViolation {
+    pub rowid: i64,
+    pub domain: i64,
+    pub servers: i32,
+    pub cname_at_apex: bool,
+    pub cname_with_other_data: bool,
+    pub other_types: Option<String>,
+    pub query_time: i64,
+    pub resolver: Option<String>,
+}
+
+#[derive(Insertable)]
+#[table_name = "apex_violation"]
+pub struct NewApexViolation<'a> {
+    pub domain: &'a i64,
+    pub servers: &'a i32,
+    pub cname_at_apex: &'a bool,
+    pub cname_with_other_data: &'a bool,
+    pub other_types: Option<&'a str>,
+    pub query_time: &'a i64,
+    pub resolver: Option<&'a str>,
+}
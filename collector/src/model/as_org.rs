@@ -0,0 +1,25 @@
+use super::super::schema::as_org;
+
+#[derive(Queryable)]
+pub struct AsOrg {
+    pub rowid: i64,
+    pub number: i32,
+    pub aut_name: Option<String>,
+    pub org_id: String,
+    pub org_name: Option<String>,
+    pub country: Option<String>,
+    pub source: Option<String>,
+    pub dataset: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "as_org"]
+pub struct NewAsOrg<'a> {
+    pub number: &'a i32,
+    pub aut_name: Option<&'a str>,
+    pub org_id: &'a str,
+    pub org_name: Option<&'a str>,
+    pub country: Option<&'a str>,
+    pub source: Option<&'a str>,
+    pub dataset: &'a str,
+}
@@ -0,0 +1,16 @@
+use super::super::schema::asn;
+
+#[derive(Queryable)]
+pub struct Asn {
+    pub rowid: i64,
+    pub number: i32,
+    pub organisation: Option<String>,
+    pub org_id: Option<String>,
+}
+
+#[derive(Insertable)]
+#[table_name = "asn"]
+pub struct NewAsn<'a> {
+    pub number: &'a i32,
+    pub organisation: Option<&'a str>,
+}
@@ -0,0 +1,45 @@
+use super::super::schema::{dnssec_record, dnssec_status};
+
+#[derive(Queryable)]
+pub struct DnssecRecord {
+    pub rowid: i64,
+    pub query_name: String,
+    pub query_type: String,
+    pub response_code: i32,
+    pub section: Option<String>,
+    pub record_type: Option<String>,
+    pub data: Option<Vec<u8>>,
+    pub query_time: i64,
+    pub resolver: Option<String>,
+}
+
+#[derive(Insertable)]
+#[table_name = "dnssec_record"]
+pub struct NewDnssecRecord<'a> {
+    pub query_name: &'a str,
+    pub query_type: &'a str,
+    pub response_code: &'a i32,
+    pub section: Option<&'a str>,
+    pub record_type: Option<&'a str>,
+    pub data: Option<&'a [u8]>,
+    pub query_time: &'a i64,
+    pub resolver: Option<&'a str>,
+}
+
+#[derive(Queryable)]
+pub struct DnssecStatus {
+    pub rowid: i64,
+    pub domain: i64,
+    pub status: String,
+    pub reason: String,
+    pub validation_time: i64,
+}
+
+#[derive(Insertable)]
+#[table_name = "dnssec_status"]
+pub struct NewDnssecStatus<'a> {
+    pub domain: &'a i64,
+    pub status: &'a str,
+    pub reason: &'a str,
+    pub validation_time: &'a i64,
+}
@@ -0,0 +1,33 @@
+use super::super::schema::enrichment;
+
+#[derive(Queryable)]
+pub struct Enrichment {
+    pub rowid: i64,
+    pub address: String,
+    pub database_type: String,
+    pub build_date: String,
+    pub asn: Option<i32>,
+    pub organisation: Option<String>,
+    pub prefix: Option<String>,
+    pub country: Option<String>,
+    pub city: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub enrich_time: i64,
+}
+
+#[derive(Insertable)]
+#[table_name = "enrichment"]
+pub struct NewEnrichment<'a> {
+    pub address: &'a str,
+    pub database_type: &'a str,
+    pub build_date: &'a str,
+    pub asn: Option<&'a i32>,
+    pub organisation: Option<&'a str>,
+    pub prefix: Option<&'a str>,
+    pub country: Option<&'a str>,
+    pub city: Option<&'a str>,
+    pub latitude: Option<&'a f64>,
+    pub longitude: Option<&'a f64>,
+    pub enrich_time: &'a i64,
+}
@@ -0,0 +1,23 @@
+use super::super::schema::hosting;
+
+#[derive(Queryable)]
+pub struct Hosting {
+    pub rowid: i64,
+    pub domain: i64,
+    pub is_www: bool,
+    pub provider: String,
+    pub confidence: String,
+    pub cname_provider: Option<String>,
+    pub asn_provider: Option<String>,
+}
+
+#[derive(Insertable)]
+#[table_name = "hosting"]
+pub struct NewHosting<'a> {
+    pub domain: &'a i64,
+    pub is_www: &'a bool,
+    pub provider: &'a str,
+    pub confidence: &'a str,
+    pub cname_provider: Option<&'a str>,
+    pub asn_provider: Option<&'a str>,
+}
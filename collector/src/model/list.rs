@@ -0,0 +1,41 @@
+use super::super::schema::{list, list_member};
+
+#[derive(Queryable)]
+pub struct List {
+    pub rowid: i64,
+    pub name: String,
+    pub provider: Option<String>,
+    pub list_id: Option<String>,
+    pub format: String,
+    pub download_date: String,
+    pub file_hash: String,
+    pub load_time: i64,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "list"]
+pub struct NewList<'a> {
+    pub name: &'a str,
+    pub provider: Option<&'a str>,
+    pub list_id: Option<&'a str>,
+    pub format: &'a str,
+    pub download_date: &'a str,
+    pub file_hash: &'a str,
+    pub load_time: &'a i64,
+}
+
+#[derive(Queryable)]
+pub struct ListMember {
+    pub rowid: i64,
+    pub list: i64,
+    pub domain: i64,
+    pub rank: i32,
+}
+
+#[derive(Insertable)]
+#[table_name = "list_member"]
+pub struct NewListMember<'a> {
+    pub list: &'a i64,
+    pub domain: &'a i64,
+    pub rank: &'a i32,
+}
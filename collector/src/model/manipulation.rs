@@ -0,0 +1,29 @@
+use super::super::schema::manipulation;
+
+#[derive(Queryable)]
+pub struct Manipulation {
+    pub rowid: i64,
+    pub domain: i64,
+    pub is_www: bool,
+    pub query_type: String,
+    pub resolver: String,
+    pub response_code: i32,
+    pub servers: i32,
+    pub verdict: String,
+    pub reason: Option<String>,
+    pub query_time: i64,
+}
+
+#[derive(Insertable)]
+#[table_name = "manipulation"]
+pub struct NewManipulation<'a> {
+    pub domain: &'a i64,
+    pub is_www: &'a bool,
+    pub query_type: &'a str,
+    pub resolver: &'a str,
+    pub response_code: &'a i32,
+    pub servers: &'a i32,
+    pub verdict: &'a str,
+    pub reason: Option<&'a str>,
+    pub query_time: &'a i64,
+}
@@ -1,9 +1,31 @@
+pub mod answer;
+pub mod apex_alias;
+pub mod apex_violation;
+pub mod as_org;
+pub mod asn;
+pub mod dnssec;
 pub mod domain;
+pub mod enrichment;
+pub mod hosting;
+pub mod list;
+pub mod manipulation;
+pub mod ptr;
 pub mod record;
+pub mod rejected_domain;
+pub mod wildcard;
 
+use diesel::connection::SimpleConnection;
 use diesel::prelude::*;
 
-/// Open the SQLite database for use
+no_arg_sql_function!(
+    last_insert_rowid,
+    diesel::sql_types::BigInt,
+    "The rowid of the last row inserted by this connection"
+);
+
+/// Open the SQLite database for use. As several connections may write to the
+/// database at once, e.g. when querying several resolvers, each will wait for
+/// a lock to be released rather than failing immediately.
 ///
 /// # Arguments:
 /// * `database_url` - Path to the SQLite file
@@ -15,6 +37,9 @@ use diesel::prelude::*;
 /// let conn = connect(":memory:".to_string());
 /// ```
 pub fn connect(database_url: String) -> SqliteConnection {
-    SqliteConnection::establish(&database_url)
-        .expect(&format!("Error connecting to {}", database_url))
+    let conn = SqliteConnection::establish(&database_url)
+        .expect(&format!("Error connecting to {}", database_url));
+    conn.batch_execute("PRAGMA busy_timeout = 60000;")
+        .expect(&format!("Error configuring {}", database_url));
+    conn
 }
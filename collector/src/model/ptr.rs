@@ -0,0 +1,23 @@
+use super::super::schema::ptr;
+
+#[derive(Queryable)]
+pub struct Ptr {
+    pub rowid: i64,
+    pub address: String,
+    pub response_code: i32,
+    pub host: Option<String>,
+    pub ttl: Option<i32>,
+    pub query_time: i64,
+    pub resolver: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "ptr"]
+pub struct NewPtr<'a> {
+    pub address: &'a str,
+    pub response_code: &'a i32,
+    pub host: Option<&'a str>,
+    pub ttl: Option<&'a i32>,
+    pub query_time: &'a i64,
+    pub resolver: &'a str,
+}
@@ -12,6 +12,24 @@ pub struct Record {
     pub address: Option<String>,
     pub asn: Option<i32>,
     pub query_time: i64,
+    pub provider: Option<String>,
+    pub client_subnet: Option<String>,
+    pub scope_prefix: Option<i32>,
+    pub resolver: Option<String>,
+    pub authoritative_ttl: Option<i32>,
+    pub cache_age: Option<i32>,
+    pub prefix: Option<String>,
+    pub asn_source: Option<String>,
+    pub country: Option<String>,
+    pub city: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub cloud_provider: Option<String>,
+    pub cloud_service: Option<String>,
+    pub cloud_region: Option<String>,
+    pub special_purpose: Option<String>,
+    pub bogon: Option<bool>,
+    pub rov: Option<String>,
 }
 
 #[derive(Insertable)]
@@ -26,4 +44,21 @@ pub struct NewRecord<'a> {
     pub address: Option<&'a str>,
     pub asn: Option<&'a i32>,
     pub query_time: &'a i64,
+    pub client_subnet: Option<&'a str>,
+    pub scope_prefix: Option<&'a i32>,
+    pub resolver: Option<&'a str>,
+    pub authoritative_ttl: Option<&'a i32>,
+    pub cache_age: Option<&'a i32>,
+    pub prefix: Option<&'a str>,
+    pub asn_source: Option<&'a str>,
+    pub country: Option<&'a str>,
+    pub city: Option<&'a str>,
+    pub latitude: Option<&'a f64>,
+    pub longitude: Option<&'a f64>,
+    pub cloud_provider: Option<&'a str>,
+    pub cloud_service: Option<&'a str>,
+    pub cloud_region: Option<&'a str>,
+    pub special_purpose: Option<&'a str>,
+    pub bogon: Option<&'a bool>,
+    pub rov: Option<&'a str>,
 }
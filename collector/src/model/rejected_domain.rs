@@ -0,0 +1,21 @@
+use super::super::schema::rejected_domain;
+
+#[derive(Queryable)]
+pub struct RejectedDomain {
+    pub rowid: i64,
+    pub list: i64,
+    pub line: i64,
+    pub raw: String,
+    pub reason: String,
+    pub error: Option<String>,
+}
+
+#[derive(Insertable)]
+#[table_name = "rejected_domain"]
+pub struct NewRejectedDomain<'a> {
+    pub list: &'a i64,
+    pub line: &'a i64,
+    pub raw: &'a str,
+    pub reason: &'a str,
+    pub error: Option<&'a str>,
+}
@@ -0,0 +1,29 @@
+use super::super::schema::wildcard;
+
+#[derive(Queryable)]
+pub struct Wildcard {
+    pub rowid: i64,
+    pub domain: i64,
+    pub label: String,
+    pub is_wildcard: bool,
+    pub response_code: i32,
+    pub record_type: Option<String>,
+    pub ttl: Option<i32>,
+    pub address: Option<String>,
+    pub query_time: i64,
+    pub resolver: Option<String>,
+}
+
+#[derive(Insertable)]
+#[table_name = "wildcard"]
+pub struct NewWildcard<'a> {
+    pub domain: &'a i64,
+    pub label: &'a str,
+    pub is_wildcard: &'a bool,
+    pub response_code: &'a i32,
+    pub record_type: Option<&'a str>,
+    pub ttl: Option<&'a i32>,
+    pub address: Option<&'a str>,
+    pub query_time: &'a i64,
+    pub resolver: Option<&'a str>,
+}
@@ -0,0 +1,379 @@
+use failure::Error;
+use sha2::{Digest, Sha256};
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+use std::str::FromStr;
+
+/// The format of a domain ranking list.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ListFormat {
+    /// Headerless `rank,domain`, common to many lists
+    Csv,
+    /// Tranco, headerless `rank,domain`
+    Tranco,
+    /// Majestic Million, with a header naming `GlobalRank` and `Domain`
+    /// among others
+    Majestic,
+    /// Cisco Umbrella, headerless `rank,domain` of any name resolved
+    Umbrella,
+    /// Alexa, headerless `rank,domain`
+    Alexa,
+    /// Cloudflare Radar, with a header naming `domain` and no rank, each file
+    /// being a bucket of the top N domains
+    Radar,
+    /// Chrome UX Report, with a header naming `origin` and `rank`, the rank
+    /// being a bucket of the top N origins
+    Crux,
+}
+
+impl ListFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ListFormat::Csv => "csv",
+            ListFormat::Tranco => "tranco",
+            ListFormat::Majestic => "majestic",
+            ListFormat::Umbrella => "umbrella",
+            ListFormat::Alexa => "alexa",
+            ListFormat::Radar => "radar",
+            ListFormat::Crux => "crux",
+        }
+    }
+
+    /// The organisation publishing lists of the format, where known.
+    pub fn provider(&self) -> Option<&'static str> {
+        match self {
+            ListFormat::Csv => None,
+            ListFormat::Tranco => Some("Tranco"),
+            ListFormat::Majestic => Some("Majestic"),
+            ListFormat::Umbrella => Some("Cisco"),
+            ListFormat::Alexa => Some("Alexa"),
+            ListFormat::Radar => Some("Cloudflare"),
+            ListFormat::Crux => Some("Google"),
+        }
+    }
+
+    /// Whether the list ranks domains into buckets, e.g. the top 1,000, rather
+    /// than giving each its own rank.
+    pub fn is_bucketed(&self) -> bool {
+        match self {
+            ListFormat::Radar | ListFormat::Crux => true,
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for ListFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<ListFormat, Error> {
+        match s {
+            "csv" => Ok(ListFormat::Csv),
+            "tranco" => Ok(ListFormat::Tranco),
+            "majestic" => Ok(ListFormat::Majestic),
+            "umbrella" => Ok(ListFormat::Umbrella),
+            "alexa" => Ok(ListFormat::Alexa),
+            "radar" => Ok(ListFormat::Radar),
+            "crux" => Ok(ListFormat::Crux),
+            _ => Err(failure::format_err!("Unknown list format '{}'", s)),
+        }
+    }
+}
+
+/// A domain and its rank as read from a list. For bucketed lists the rank is
+/// the size of the bucket, e.g. 1000 for the top 1,000.
+#[derive(Debug, PartialEq)]
+pub struct RankedDomain {
+    /// Rank of the domain, or `None` where it is not a number
+    pub rank: Option<i32>,
+    pub domain: String,
+    /// Line of the file the domain was read from, the first of them where a
+    /// quoted field spans several
+    pub line: u64,
+    /// The line as read, without its line ending, any invalid UTF-8 being
+    /// replaced
+    pub raw: String,
+    /// Why the line could not be read as CSV, e.g. it is not UTF-8, in which
+    /// case it has neither rank nor domain
+    pub invalid: Option<String>,
+}
+
+impl RankedDomain {
+    fn invalid(line: u64, raw: String, reason: String) -> RankedDomain {
+        RankedDomain {
+            rank: None,
+            domain: String::new(),
+            line,
+            raw,
+            invalid: Some(reason),
+        }
+    }
+}
+
+/// Reads the domains of a ranking list of any `ListFormat`.
+///
+/// ```
+/// use damp::ranking::{ListFormat, ListReader};
+///
+/// let csv = "GlobalRank,TldRank,Domain,TLD\n1,1,google.com,com\n2,2,facebook.com,com\n";
+/// let mut list = ListReader::new(ListFormat::Majestic, csv.as_bytes(), None).unwrap();
+/// let first = list.next().unwrap().unwrap();
+/// assert_eq!((first.rank, first.domain.as_str()), (Some(1), "google.com"));
+///
+/// let csv = "origin,rank\nhttps://www.google.com,1000\nhttp://example.com:8080,5000\n";
+/// let list = ListReader::new(ListFormat::Crux, csv.as_bytes(), None).unwrap();
+/// let domains: Vec<String> = list.map(|d| d.unwrap().domain).collect();
+/// assert_eq!(domains, vec!["www.google.com", "example.com"]);
+///
+/// let csv = "domain\ngoogle.com\n";
+/// let mut list = ListReader::new(ListFormat::Radar, csv.as_bytes(), Some(200)).unwrap();
+/// assert_eq!(list.next().unwrap().unwrap().rank, Some(200));
+///
+/// let csv = "1,google.com\nfirst,facebook.com\n";
+/// let list = ListReader::new(ListFormat::Csv, csv.as_bytes(), None).unwrap();
+/// let ranks: Vec<Option<i32>> = list.map(|d| d.unwrap().rank).collect();
+/// assert_eq!(ranks, vec![Some(1), None]);
+///
+/// let csv = "1,\"google.com\" \r\n";
+/// let mut list = ListReader::new(ListFormat::Tranco, csv.as_bytes(), None).unwrap();
+/// let first = list.next().unwrap().unwrap();
+/// assert_eq!((first.domain.as_str(), first.raw.as_str()), ("google.com", "1,\"google.com\" "));
+///
+/// let csv = "1,\"google\n.com\"\n2,facebook.com\n";
+/// let list = ListReader::new(ListFormat::Csv, csv.as_bytes(), None).unwrap();
+/// let lines: Vec<(u64, String)> = list.map(|d| d.unwrap()).map(|d| (d.line, d.raw)).collect();
+/// assert_eq!(lines, vec![(1, "1,\"google\n.com\"".to_string()), (3, "2,facebook.com".to_string())]);
+///
+/// let csv = b"1,goo\xffgle.com\n2,facebook.com\n";
+/// let mut list = ListReader::new(ListFormat::Csv, &csv[..], None).unwrap();
+/// let first = list.next().unwrap().unwrap();
+/// assert_eq!((first.raw.as_str(), first.invalid.is_some()), ("1,goo\u{fffd}gle.com", true));
+/// assert_eq!(list.next().unwrap().unwrap().domain, "facebook.com");
+/// ```
+pub struct ListReader<R: Read> {
+    reader: BufReader<R>,
+    line: u64,
+    rank_column: Option<usize>,
+    domain_column: usize,
+    bucket: Option<i32>,
+    format: ListFormat,
+}
+
+impl<R: Read> ListReader<R> {
+    /// Begin reading the list, reading its header where the format has one.
+    ///
+    /// # Arguments
+    /// * `format` - Format of the list
+    /// * `reader` - The list
+    /// * `bucket` - Rank given to every domain of a list without ranks, i.e.
+    ///   Cloudflare Radar, being the size of its bucket
+    pub fn new(format: ListFormat, reader: R, bucket: Option<i32>) -> Result<ListReader<R>, Error> {
+        let has_headers = match format {
+            ListFormat::Majestic | ListFormat::Radar | ListFormat::Crux => true,
+            _ => false,
+        };
+        let mut reader = BufReader::new(reader);
+        let mut line = 0;
+        let headers = match has_headers {
+            true => match next_record(&mut reader, &mut line) {
+                Some(record) => fields(&String::from_utf8(record?.1)?)?,
+                None => csv::StringRecord::new(),
+            },
+            false => csv::StringRecord::new(),
+        };
+
+        let (rank_column, domain_column) = match format {
+            ListFormat::Majestic => (
+                Some(column(&headers, &["GlobalRank"])?),
+                column(&headers, &["Domain"])?,
+            ),
+            ListFormat::Radar => {
+                if bucket.is_none() {
+                    return Err(failure::format_err!("Radar lists require a bucket"));
+                }
+                (None, column(&headers, &["domain"])?)
+            }
+            ListFormat::Crux => (
+                Some(column(&headers, &["rank", "experimental.popularity.rank"])?),
+                column(&headers, &["origin"])?,
+            ),
+            _ => (Some(0), 1),
+        };
+
+        Ok(ListReader {
+            reader,
+            line,
+            rank_column,
+            domain_column,
+            bucket,
+            format,
+        })
+    }
+}
+
+impl<R: Read> Iterator for ListReader<R> {
+    type Item = Result<RankedDomain, Error>;
+
+    fn next(&mut self) -> Option<Result<RankedDomain, Error>> {
+        let (line, bytes) = match next_record(&mut self.reader, &mut self.line)? {
+            Ok(r) => r,
+            Err(e) => return Some(Err(e.into())),
+        };
+        // A line that cannot be read is passed on to be rejected, rather than
+        // ending the list
+        let text = match String::from_utf8(bytes) {
+            Ok(t) => t,
+            Err(e) => {
+                let raw = String::from_utf8_lossy(e.as_bytes()).into_owned();
+                return Some(Ok(RankedDomain::invalid(line, raw, e.to_string())));
+            }
+        };
+        let record = match fields(&text) {
+            Ok(r) => r,
+            Err(e) => return Some(Ok(RankedDomain::invalid(line, text, e.to_string()))),
+        };
+        let rank = match self.rank_column {
+            Some(c) => record.get(c).and_then(|r| r.trim().parse().ok()),
+            None => self.bucket,
+        };
+        let domain = record.get(self.domain_column).unwrap_or("").trim();
+        let domain = match self.format {
+            ListFormat::Crux => origin_host(domain),
+            _ => domain,
+        };
+        Some(Ok(RankedDomain {
+            rank,
+            domain: domain.to_string(),
+            line,
+            raw: text,
+            invalid: None,
+        }))
+    }
+}
+
+/// The next line that is not blank, as its bytes without its line ending or
+/// any byte order mark, and its number. A quoted field may span lines, so
+/// lines are joined while a quote is left open. `number` counts the lines
+/// read, blank or not.
+fn next_record<B: BufRead>(reader: &mut B, number: &mut u64) -> Option<io::Result<(u64, Vec<u8>)>> {
+    loop {
+        let start = *number + 1;
+        let mut text = Vec::new();
+        loop {
+            match reader.read_until(b'\n', &mut text) {
+                Ok(0) => break,
+                Ok(_) => *number += 1,
+                Err(e) => return Some(Err(e)),
+            }
+            // Quotes within a quoted field are doubled, so an odd count of
+            // them leaves the field open
+            if text.iter().filter(|b| **b == b'"').count() % 2 == 0 {
+                break;
+            }
+        }
+        if text.is_empty() {
+            return None;
+        }
+        if text.ends_with(b"\n") {
+            text.pop();
+        }
+        if text.ends_with(b"\r") {
+            text.pop();
+        }
+        if start == 1 && text.starts_with("\u{feff}".as_bytes()) {
+            text.drain(..3);
+        }
+        if !text.is_empty() {
+            return Some(Ok((start, text)));
+        }
+    }
+}
+
+/// The fields of a line of the list, parsed as CSV. Each line is parsed
+/// alone so that the original text is kept for any rejected.
+fn fields(line: &str) -> Result<csv::StringRecord, Error> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(line.as_bytes());
+    let mut record = csv::StringRecord::new();
+    reader.read_record(&mut record)?;
+    Ok(record)
+}
+
+/// Index of the first of the named columns present in the header.
+fn column(headers: &csv::StringRecord, names: &[&str]) -> Result<usize, Error> {
+    headers
+        .iter()
+        .position(|h| names.contains(&h.trim()))
+        .ok_or_else(|| failure::format_err!("No {} column in header", names[0]))
+}
+
+/// The host of a web origin, e.g. "www.example.com" of
+/// "https://www.example.com:443".
+fn origin_host(origin: &str) -> &str {
+    let host = match origin.find("://") {
+        Some(i) => &origin[i + 3..],
+        None => origin,
+    };
+    host.split(|c| c == ':' || c == '/').next().unwrap_or(host)
+}
+
+/// The size of the bucket given in the file name of a list, as Cloudflare
+/// Radar names its files, e.g. "cloudflare-radar-domains-top-2000-....csv".
+///
+/// ```
+/// use damp::ranking::bucket_from_path;
+///
+/// let path = "cloudflare-radar-domains-top-2000-20221017-20221024.csv";
+/// assert_eq!(bucket_from_path(path), Some(2000));
+/// assert_eq!(bucket_from_path("top-1m.csv"), None);
+/// ```
+pub fn bucket_from_path<P: AsRef<Path>>(path: P) -> Option<i32> {
+    let name = path.as_ref().file_name()?.to_str()?;
+    let start = name.find("top-")? + 4;
+    let digits: String = name[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    // A count such as the "1m" of "top-1m.csv" is not a bucket
+    match name[start + digits.len()..].chars().next() {
+        Some(c) if c.is_ascii_alphanumeric() => None,
+        _ => digits.parse().ok(),
+    }
+}
+
+/// The ID of a Tranco list given in its file name, as downloaded from
+/// tranco-list.eu, e.g. "Z2XG" of "tranco_Z2XG.csv".
+///
+/// ```
+/// use damp::ranking::tranco_list_id;
+///
+/// assert_eq!(tranco_list_id("tranco_Z2XG-1m.csv"), Some("Z2XG".to_string()));
+/// assert_eq!(tranco_list_id("top-1m.csv"), None);
+/// ```
+pub fn tranco_list_id<P: AsRef<Path>>(path: P) -> Option<String> {
+    let name = path.as_ref().file_name()?.to_str()?;
+    let start = name.find("tranco_")? + 7;
+    let id: String = name[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .collect();
+    Some(id).filter(|i| !i.is_empty())
+}
+
+/// SHA-256 of the file, in hex, identifying exactly which copy of a list was
+/// loaded.
+///
+/// # Arguments
+/// * `path` - Path to the file
+pub fn file_hash<P: AsRef<Path>>(path: P) -> Result<String, Error> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 65536];
+    loop {
+        match file.read(&mut buf)? {
+            0 => break,
+            n => hasher.input(&buf[..n]),
+        }
+    }
+    Ok(format!("{:x}", hasher.result()))
+}
@@ -0,0 +1,150 @@
+use crate::asn::PrefixTrie;
+use failure::Error;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::Read;
+use std::net::IpAddr;
+use std::path::Path;
+
+/// The outcome of Route Origin Validation of a route, per RFC 6811.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RovState {
+    /// A VRP covering the route authorises its origin and length.
+    Valid,
+    /// VRPs cover the route, but none authorise its origin and length.
+    Invalid,
+    /// No VRP covers the route.
+    NotFound,
+}
+
+impl RovState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RovState::Valid => "valid",
+            RovState::Invalid => "invalid",
+            RovState::NotFound => "not_found",
+        }
+    }
+}
+
+/// A Validated ROA Payload, less its prefix.
+struct Vrp {
+    asn: u32,
+    max_length: usize,
+}
+
+/// The Validated ROA Payloads exported by a relying party, such as
+/// Routinator or rpki-client, as JSON:
+///
+/// ```text
+/// {"roas": [{"asn": "AS13335", "prefix": "1.0.0.0/24", "maxLength": 24, "ta": "apnic"}]}
+/// ```
+///
+/// The ASN may be given with or without the "AS" prefix, or as a number.
+///
+/// See Also: [RFC 6811](https://tools.ietf.org/html/rfc6811)
+#[derive(Default)]
+pub struct Vrps {
+    trie: PrefixTrie<Vec<Vrp>>,
+    prefixes: usize,
+}
+
+impl Vrps {
+    /// Load the VRPs from a JSON export on disk.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the JSON export
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Vrps, Error> {
+        let file = crate::compress::open(path)?;
+        Vrps::from_reader(file)
+    }
+
+    /// Load the VRPs from anything implementing `Read`.
+    ///
+    /// ```
+    /// use damp::rpki::{RovState, Vrps};
+    ///
+    /// let json = r#"{"roas": [
+    ///     {"asn": "AS13335", "prefix": "1.0.0.0/24", "maxLength": 24, "ta": "apnic"},
+    ///     {"asn": 15169, "prefix": "8.8.8.0/23", "maxLength": 24, "ta": "arin"}
+    /// ]}"#;
+    /// let vrps = Vrps::from_reader(json.as_bytes()).unwrap();
+    /// assert_eq!(vrps.len(), 2);
+    ///
+    /// let route = "1.0.0.0".parse().unwrap();
+    /// assert_eq!(vrps.validate(route, 24, 13335), RovState::Valid);
+    /// assert_eq!(vrps.validate(route, 24, 64496), RovState::Invalid);
+    /// assert_eq!(vrps.validate(route, 25, 13335), RovState::Invalid);
+    /// assert_eq!(vrps.validate("8.8.8.0".parse().unwrap(), 24, 15169), RovState::Valid);
+    /// assert_eq!(vrps.validate("192.0.2.0".parse().unwrap(), 24, 64496), RovState::NotFound);
+    /// ```
+    pub fn from_reader<R: Read>(reader: R) -> Result<Vrps, Error> {
+        let json: Value = serde_json::from_reader(reader)?;
+        let roas = json["roas"]
+            .as_array()
+            .ok_or_else(|| failure::format_err!("No roas in VRP export"))?;
+
+        // Several VRPs may share a prefix, so are gathered before insertion
+        let mut prefixes: HashMap<(IpAddr, usize), Vec<Vrp>> = HashMap::new();
+        for roa in roas {
+            let asn = match &roa["asn"] {
+                Value::Number(n) => n.as_u64().map(|n| n as u32),
+                Value::String(s) => s.trim_start_matches("AS").parse().ok(),
+                _ => None,
+            };
+            let mut parts = roa["prefix"].as_str().unwrap_or("").splitn(2, '/');
+            let network: Option<IpAddr> = parts.next().and_then(|n| n.parse().ok());
+            let length: Option<usize> = parts.next().and_then(|l| l.parse().ok());
+            match (asn, network, length) {
+                (Some(asn), Some(network), Some(length)) => {
+                    let max_length = roa["maxLength"].as_u64().map_or(length, |m| m as usize);
+                    prefixes
+                        .entry((network, length))
+                        .or_insert_with(Vec::new)
+                        .push(Vrp { asn, max_length });
+                }
+                _ => return Err(failure::format_err!("Invalid VRP {}", roa)),
+            }
+        }
+
+        let mut trie = PrefixTrie::new();
+        let count = prefixes.len();
+        for ((network, length), vrps) in prefixes {
+            trie.insert(network, length, vrps);
+        }
+        Ok(Vrps {
+            trie,
+            prefixes: count,
+        })
+    }
+
+    /// Number of distinct prefixes with VRPs.
+    pub fn len(&self) -> usize {
+        self.prefixes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Validate the origin of the route.
+    ///
+    /// # Arguments
+    /// * `network` - Address of the route's prefix
+    /// * `length` - Length of the route's prefix
+    /// * `origin` - ASN originating the route
+    pub fn validate(&self, network: IpAddr, length: usize, origin: u32) -> RovState {
+        let covering = self.trie.covering(network, length);
+        if covering.is_empty() {
+            return RovState::NotFound;
+        }
+        let authorised = covering
+            .iter()
+            .flat_map(|(_, vrps)| vrps.iter())
+            .any(|v| v.asn == origin && length <= v.max_length);
+        match authorised {
+            true => RovState::Valid,
+            false => RovState::Invalid,
+        }
+    }
+}
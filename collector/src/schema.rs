@@ -18,10 +18,12 @@ table! {
         /// SQLite specific hidden row
         rowid -> BigInt,
 
-        /// Ranking of the domain as provided by the list source
+        /// Ranking of the domain in the first list it was loaded from, with
+        /// its rank in every list held by the `list_member` table
         rank -> Integer,
 
-        /// Fully Qualified Domain Name, e.g. "foo.example.co.uk"
+        /// Fully Qualified Domain Name, e.g. "foo.example.co.uk", unique
+        /// across every list loaded
         fqdn -> Text,
 
         /// Sub-domain, e.g "foo"
@@ -35,12 +37,104 @@ table! {
     }
 }
 
+table! {
+    /// # List
+    /// This table represents the ranking lists loaded by the domain_load(8)
+    /// binary, such that a database may hold several lists at once, e.g.
+    /// Tranco, Umbrella and Majestic, with each domain queried only once.
+    list (rowid) {
+        /// SQLite specific hidden row
+        rowid -> BigInt,
+
+        /// Name of the list, by default its file name, e.g. "top-1m.csv".
+        /// A list loaded again under the same name replaces its ranks.
+        name -> Text,
+
+        /// The organisation publishing the list, e.g. "Tranco" or "Cisco".
+        provider -> Nullable<Text>,
+
+        /// Identifier of the list given by its provider, e.g. the Tranco list
+        /// ID "Z2XG".
+        list_id -> Nullable<Text>,
+
+        /// Format the list was read in, e.g. "tranco" or "majestic".
+        format -> Text,
+
+        /// Date the list was downloaded, e.g. "2019-11-19", by default the
+        /// date the file was last modified.
+        download_date -> Text,
+
+        /// SHA-256 of the file loaded, in hex.
+        file_hash -> Text,
+
+        /// Time the list was loaded, represented as UTC derived from Unix
+        /// Epoch.
+        load_time -> BigInt,
+    }
+}
+
+table! {
+    /// # List Member
+    /// This table represents the membership of domains in each list, along
+    /// with their rank in that list. A domain is a member of a list once.
+    list_member (rowid) {
+        /// SQLite specific hidden row
+        rowid -> BigInt,
+
+        /// Foreign key to the `list` table.
+        list -> BigInt,
+
+        /// Foreign key to the `domain` table.
+        domain -> BigInt,
+
+        /// Ranking of the domain in the list. For bucketed lists, e.g.
+        /// Cloudflare Radar or the Chrome UX Report, the size of its bucket.
+        rank -> Integer,
+    }
+}
+
+table! {
+    /// # Rejected Domain
+    /// This table represents the lines of a list which domain_load(8) could
+    /// not load, such that issues with the quality of a list may be audited.
+    /// A line is rejected for one of the following reasons:
+    ///
+    /// * `invalid_line` - The line could not be read as CSV, e.g. it is not
+    ///   UTF-8
+    /// * `invalid_rank` - The rank is not a number
+    /// * `invalid_domain` - The domain could not be parsed against the
+    ///   Public Suffix List
+    /// * `insert_failed` - The domain could not be inserted into the database
+    rejected_domain (rowid) {
+        /// SQLite specific hidden row
+        rowid -> BigInt,
+
+        /// Foreign key to the `list` table.
+        list -> BigInt,
+
+        /// Line of the file the domain was read from, from 1.
+        line -> BigInt,
+
+        /// The line exactly as read, without its line ending, any invalid
+        /// UTF-8 being replaced. A quoted field may span several lines.
+        raw -> Text,
+
+        /// Reason the line was rejected, e.g. "invalid_rank".
+        reason -> Text,
+
+        /// The error raised, where there was one.
+        error -> Nullable<Text>,
+    }
+}
+
 table! {
     /// # Record
     /// This table represents the answers performed for the DNS queries made.
     /// Each row represents a record returned from any number of DNS queries
     /// performed - one query may result in `n`, for example a query for
     /// `IN A example.com` may return `CNAME example.net` and `A 127.0.0.1`.
+    /// A response without any answers, e.g. `NXDOMAIN`, is a single row with
+    /// a `NULL` record type and address.
     record (domain) {
         /// SQLite specific hidden row
         rowid -> BigInt,
@@ -80,5 +174,587 @@ table! {
         /// Time the DNS query was performed, represented as UTC derived from
         /// Unix Epoch at millisecond resolution.
         query_time -> BigInt,
+
+        /// Hosting or CDN provider the CNAME target of this record belongs
+        /// to, as matched against the fingerprint database.
+        provider -> Nullable<Text>,
+
+        /// The EDNS Client Subnet attached to the query, e.g. "192.0.2.0/24",
+        /// or `NULL` where the query was sent without one.
+        client_subnet -> Nullable<Text>,
+
+        /// The scope prefix length returned by the resolver for the client
+        /// subnet, denoting how much of the subnet the answer applies to.
+        /// `NULL` should the resolver not return the option.
+        scope_prefix -> Nullable<Integer>,
+
+        /// Socket address of the resolver the query was sent to, e.g.
+        /// "8.8.8.8:53".
+        resolver -> Nullable<Text>,
+
+        /// The TTL of the record as configured, taken from an authoritative
+        /// server of the zone. `NULL` where not requested or where the record
+        /// is outside of the zone, e.g. the target of a CNAME.
+        authoritative_ttl -> Nullable<Integer>,
+
+        /// Seconds the answer had been cached by the resolver, inferred from
+        /// how far `ttl` has counted down from `authoritative_ttl`. `NULL`
+        /// where the resolver's TTL is higher, as it has imposed its own.
+        cache_age -> Nullable<Integer>,
+
+        /// The network the address was matched in when looking up its ASN,
+        /// in CIDR notation, e.g. "192.0.2.0/24".
+        prefix -> Nullable<Text>,
+
+        /// The source the ASN was looked up in, e.g.
+        /// "mmdb:GeoLite2-ASN/2019-11-19" or "prefix2as:routeviews-rv2-20191118-1200.pfx2as".
+        asn_source -> Nullable<Text>,
+
+        /// ISO 3166-1 code of the country the address is located in, e.g.
+        /// "GB". `NULL` unless a GeoLite2 database was given.
+        country -> Nullable<Text>,
+
+        /// English name of the city the address is located in, only known
+        /// from a GeoLite2 City database.
+        city -> Nullable<Text>,
+
+        /// Approximate latitude of the address.
+        latitude -> Nullable<Double>,
+
+        /// Approximate longitude of the address.
+        longitude -> Nullable<Double>,
+
+        /// Cloud provider whose published IP ranges contain the address, e.g.
+        /// "AWS", "GCP", "Azure", "Oracle" or "Cloudflare".
+        cloud_provider -> Nullable<Text>,
+
+        /// Service of the cloud provider the range is published for, e.g.
+        /// "CLOUDFRONT". `NULL` where the provider does not publish it.
+        cloud_service -> Nullable<Text>,
+
+        /// Region of the cloud provider the range is published for, e.g.
+        /// "eu-west-1". `NULL` where the provider does not publish it.
+        cloud_region -> Nullable<Text>,
+
+        /// Name of the IANA special-purpose block the address is within, e.g.
+        /// "loopback", "private" or "documentation". `NULL` for ordinary
+        /// addresses.
+        special_purpose -> Nullable<Text>,
+
+        /// Whether the address should never be a public answer, being either
+        /// not globally reachable or within a bogon list. `NULL` where the
+        /// answer is not an address.
+        bogon -> Nullable<Bool>,
+
+        /// RPKI Route Origin Validation state of the announced route covering
+        /// the address and its origin ASN, one of "valid", "invalid" or
+        /// "not_found". `NULL` where no VRPs were given or no announced route
+        /// was matched, as is always the case for a MaxMind ASN source.
+        rov -> Nullable<Text>,
+    }
+}
+
+table! {
+    /// # Hosting
+    /// This table represents the provider that a domain's website is hosted
+    /// by, derived from matching CNAME targets and the ASN of answers against
+    /// the fingerprint database. The `www` name is preferred, as that is where
+    /// a CNAME may legitimately be used, falling back to the apex. Each domain
+    /// has a single label, replaced when hosting is classified again.
+    hosting (domain) {
+        /// SQLite specific hidden row
+        rowid -> BigInt,
+
+        /// row-id of the domain the label was assigned to.
+        domain -> BigInt,
+
+        /// Set true if the label was derived from the answers for "www".
+        is_www -> Bool,
+
+        /// Name of the provider the website is hosted by.
+        provider -> Text,
+
+        /// How confident the label is - "high" where the CNAME target and ASN
+        /// agree, "medium" for a CNAME target alone and "low" for ASN alone.
+        confidence -> Text,
+
+        /// Provider matched against the CNAME target, if any.
+        cname_provider -> Nullable<Text>,
+
+        /// Provider matched against the ASN of the answer, if any.
+        asn_provider -> Nullable<Text>,
+    }
+}
+
+table! {
+    /// # Apex Alias
+    /// This table represents how the apex of a domain is configured when the
+    /// `www` name is a CNAME, comparing the addresses of the apex with those
+    /// that the CNAME target resolves to. This identifies apexes which are
+    /// served by ALIAS/ANAME records or CNAME flattening. Each domain has a
+    /// single row, replaced when its apex is classified again.
+    apex_alias (domain) {
+        /// SQLite specific hidden row
+        rowid -> BigInt,
+
+        /// row-id of the domain that was classified.
+        domain -> BigInt,
+
+        /// The final target of the `www` CNAME chain.
+        cname_target -> Text,
+
+        /// One of "flattened", "same_provider", "different_provider",
+        /// "missing", "unresolved" or "failed".
+        classification -> Text,
+
+        /// Count of addresses returned for both the apex and CNAME target.
+        shared_addresses -> Integer,
+
+        /// Count of ASNs common to the apex and CNAME target addresses.
+        shared_asns -> Integer,
+
+        /// Time the CNAME target was resolved, represented as UTC derived
+        /// from Unix Epoch.
+        query_time -> BigInt,
+    }
+}
+
+table! {
+    /// # Apex Violation
+    /// This table represents domains whose apex answered with a CNAME via the
+    /// resolver, confirmed by querying the zone's authoritative servers
+    /// directly. A CNAME at the apex violates
+    /// [RFC 1034 &sect; 3.6.2](https://tools.ietf.org/html/rfc1034#section-3.6.2)
+    /// as it must coexist with the SOA and NS records of the zone.
+    apex_violation (domain) {
+        /// SQLite specific hidden row
+        rowid -> BigInt,
+
+        /// row-id of the domain whose apex was checked.
+        domain -> BigInt,
+
+        /// Count of authoritative servers which answered the queries.
+        servers -> Integer,
+
+        /// Set true if an authoritative server answered with a CNAME owned
+        /// by the apex.
+        cname_at_apex -> Bool,
+
+        /// Set true if the CNAME at the apex coexists with other data.
+        cname_with_other_data -> Bool,
+
+        /// Comma separated record types found alongside the CNAME, e.g.
+        /// "SOA,NS".
+        other_types -> Nullable<Text>,
+
+        /// Time the authoritative servers were queried, represented as UTC
+        /// derived from Unix Epoch.
+        query_time -> BigInt,
+
+        /// Socket address of the resolver through which the authoritative
+        /// servers were found, e.g. "8.8.8.8:53".
+        resolver -> Nullable<Text>,
+    }
+}
+
+table! {
+    /// # Wildcard
+    /// This table represents the answers to a query for a random, unlikely
+    /// label (e.g. `0b7c61a2-....example.com`) under each apex. Any positive
+    /// answer is synthesised from a wildcard record, and so answers for `www`
+    /// matching it may be discounted as not being explicitly configured.
+    /// Each answer in the response is a row, with a single row of `NULL`
+    /// record type and address where there are none (e.g. `NXDOMAIN`).
+    wildcard (domain) {
+        /// SQLite specific hidden row
+        rowid -> BigInt,
+
+        /// row-id of the domain the probe was performed under.
+        domain -> BigInt,
+
+        /// The random label that was queried.
+        label -> Text,
+
+        /// Set true if the probe received any answers.
+        is_wildcard -> Bool,
+
+        /// RCODE value from the Answer.
+        response_code -> Integer,
+
+        /// Record Type, e.g "CNAME", "A" etc.
+        record_type -> Nullable<Text>,
+
+        /// The record's Time To Live value.
+        ttl -> Nullable<Integer>,
+
+        /// Address provided in the record, this may be a FQDN or IP address.
+        address -> Nullable<Text>,
+
+        /// Time the DNS query was performed, represented as UTC derived from
+        /// Unix Epoch.
+        query_time -> BigInt,
+
+        /// Socket address of the resolver the query was sent to, e.g.
+        /// "8.8.8.8:53".
+        resolver -> Nullable<Text>,
+    }
+}
+
+table! {
+    /// # DNSSEC Record
+    /// This table represents the records collected, with the DO bit set, in
+    /// order to validate the chain of trust of each apex offline. For every
+    /// name from the root down to the apex the DNSKEY and DS record sets are
+    /// queried, along with the SOA of the apex. Names shared by domains, such
+    /// as the root and TLDs, are only queried once.
+    ///
+    /// Each record from the answer and authority sections is a row, persisted
+    /// in DNS wire format so that signatures may be verified over it exactly
+    /// as received. A single row with `NULL` section and data denotes a query
+    /// which returned no records at all.
+    dnssec_record (query_name) {
+        /// SQLite specific hidden row
+        rowid -> BigInt,
+
+        /// The name queried, lower case and without the trailing dot, save
+        /// for the root which is ".".
+        query_name -> Text,
+
+        /// The record type queried, e.g. "DNSKEY", "DS" or "SOA".
+        query_type -> Text,
+
+        /// RCODE value from the Answer.
+        response_code -> Integer,
+
+        /// Section of the response the record was found in, either "answer"
+        /// or "authority".
+        section -> Nullable<Text>,
+
+        /// Record Type, e.g "DNSKEY", "RRSIG", "NSEC" etc.
+        record_type -> Nullable<Text>,
+
+        /// The complete resource record in DNS wire format.
+        data -> Nullable<Binary>,
+
+        /// Time the DNS query was performed, represented as UTC derived from
+        /// Unix Epoch.
+        query_time -> BigInt,
+
+        /// Socket address of the resolver the query was sent to, e.g.
+        /// "8.8.8.8:53".
+        resolver -> Nullable<Text>,
+    }
+}
+
+table! {
+    /// # DNSSEC Status
+    /// This table represents the outcome of validating the chain of trust
+    /// from the configured trust anchor to the apex of each domain, using only
+    /// the records held in `dnssec_record`. Each domain has a single row,
+    /// replaced when it is validated again.
+    dnssec_status (domain) {
+        /// SQLite specific hidden row
+        rowid -> BigInt,
+
+        /// row-id of the domain that was validated.
+        domain -> BigInt,
+
+        /// One of "secure", "insecure", "bogus" or "indeterminate".
+        status -> Text,
+
+        /// Human readable reason for the status, e.g. the record set whose
+        /// signature failed to validate.
+        reason -> Text,
+
+        /// Time at which signature validity periods were evaluated - the
+        /// latest time the records for the domain were collected.
+        validation_time -> BigInt,
+    }
+}
+
+table! {
+    /// # Manipulation
+    /// This table represents the verdict on whether a resolver has tampered
+    /// with its response to an A or AAAA query of the apex or `www`, such as
+    /// rewriting `NXDOMAIN` to an ad server or answering with a sinkhole
+    /// address. The zone's authoritative servers are queried directly for the
+    /// same name and type, and their answers taken as ground truth - the
+    /// response is only considered manipulated where it differs from all of
+    /// them.
+    manipulation (domain) {
+        /// SQLite specific hidden row
+        rowid -> BigInt,
+
+        /// row-id of the domain from which the DNS query was derived from.
+        domain -> BigInt,
+
+        /// Set true if the query was for www - e.g. "www.example.com".
+        is_www -> Bool,
+
+        /// Record Type queried, e.g. "A" or "AAAA".
+        query_type -> Text,
+
+        /// Socket address of the resolver the query was sent to, e.g.
+        /// "8.8.8.8:53".
+        resolver -> Text,
+
+        /// RCODE value from the resolver's Answer.
+        response_code -> Integer,
+
+        /// Count of authoritative servers that gave an authoritative answer.
+        servers -> Integer,
+
+        /// Verdict of the comparison, one of "consistent",
+        /// "nxdomain_rewritten", "sinkhole", "private_answer", "filtered",
+        /// "unexpected_answer" or "unverified".
+        verdict -> Text,
+
+        /// Description of how the response differs from the authoritative
+        /// answers, `NULL` when consistent.
+        reason -> Nullable<Text>,
+
+        /// Time the DNS query was performed, represented as UTC derived from
+        /// Unix Epoch.
+        query_time -> BigInt,
+    }
+}
+
+table! {
+    /// # Answer Sample
+    /// This table represents the answers to A and AAAA queries of the apex and
+    /// `www` repeated a number of times, spaced apart, to reveal round-robin
+    /// and rotating answers that a single query would hide. Each row is the
+    /// answer to one query.
+    answer_sample (domain) {
+        /// SQLite specific hidden row
+        rowid -> BigInt,
+
+        /// row-id of the domain from which the DNS query was derived from.
+        domain -> BigInt,
+
+        /// Set true if the query was for www - e.g. "www.example.com".
+        is_www -> Bool,
+
+        /// Record Type queried, e.g. "A" or "AAAA".
+        query_type -> Text,
+
+        /// Socket address of the resolver the query was sent to, e.g.
+        /// "8.8.8.8:53".
+        resolver -> Text,
+
+        /// Position of the query in the series, starting from 0.
+        sample -> Integer,
+
+        /// RCODE value from the Answer.
+        response_code -> Integer,
+
+        /// Comma separated addresses answered, in the order they were
+        /// received, or `NULL` where there were none.
+        addresses -> Nullable<Text>,
+
+        /// Lowest TTL of the addresses answered.
+        ttl -> Nullable<Integer>,
+
+        /// Time the DNS query was performed, represented as UTC derived from
+        /// Unix Epoch.
+        query_time -> BigInt,
+    }
+}
+
+table! {
+    /// # Answer Stability
+    /// This table represents the metrics of how the answers in the
+    /// `answer_sample` table vary for each name, query type and resolver, as
+    /// computed by the answer_stability(8) binary, identifying DNS-based load
+    /// balancing at the apex and `www`. Each name, query type and resolver has
+    /// a single row, computed over the samples of the latest domain_query(8)
+    /// run and replaced when the metrics are computed again.
+    answer_stability (domain) {
+        /// SQLite specific hidden row
+        rowid -> BigInt,
+
+        /// row-id of the domain the samples were taken for.
+        domain -> BigInt,
+
+        /// Set true if the samples were for www - e.g. "www.example.com".
+        is_www -> Bool,
+
+        /// Record Type queried, e.g. "A" or "AAAA".
+        query_type -> Text,
+
+        /// Socket address of the resolver the samples were taken from.
+        resolver -> Text,
+
+        /// Count of queries made.
+        samples -> Integer,
+
+        /// Count of distinct address sets seen, ignoring order.
+        distinct_sets -> Integer,
+
+        /// Count of distinct addresses seen across all queries.
+        distinct_addresses -> Integer,
+
+        /// Largest number of addresses in a single answer.
+        max_set_size -> Integer,
+
+        /// Set true if the same address set was seen in a different order.
+        reordered -> Bool,
+
+        /// Lowest TTL seen.
+        ttl_min -> Nullable<Integer>,
+
+        /// Highest TTL seen.
+        ttl_max -> Nullable<Integer>,
+
+        /// Set true if the TTL only ever fell between queries, as when every
+        /// answer is served from the same cache entry.
+        ttl_decreasing -> Bool,
+
+        /// How the answers vary, one of "unanswered", "single", "static",
+        /// "round_robin" or "rotating".
+        balancing -> Text,
+
+        /// Time the metrics were computed, represented as UTC derived from
+        /// Unix Epoch.
+        analysis_time -> BigInt,
+    }
+}
+
+table! {
+    /// # PTR
+    /// This table represents the reverse DNS of the A and AAAA addresses found
+    /// in the `record` table - those of the apex, `www` and the name servers -
+    /// helping to identify hosting providers where the ASN alone is ambiguous,
+    /// e.g. a cloud provider's shared ASN. Each address is looked up only
+    /// once, with a row per PTR record answered, or a single row of `NULL`
+    /// host where there are none (e.g. `NXDOMAIN`).
+    ptr (address) {
+        /// SQLite specific hidden row
+        rowid -> BigInt,
+
+        /// IP address looked up, as stored in `record.address`.
+        address -> Text,
+
+        /// RCODE value from the Answer.
+        response_code -> Integer,
+
+        /// Host name the PTR record points to, e.g.
+        /// "ec2-192-0-2-1.compute-1.amazonaws.com."
+        host -> Nullable<Text>,
+
+        /// The record's Time To Live value.
+        ttl -> Nullable<Integer>,
+
+        /// Time the DNS query was performed, represented as UTC derived from
+        /// Unix Epoch.
+        query_time -> BigInt,
+
+        /// Socket address of the resolver the query was sent to, e.g.
+        /// "8.8.8.8:53".
+        resolver -> Text,
+    }
+}
+
+table! {
+    /// # ASN
+    /// This table represents the Autonomous Systems seen in the `asn` column
+    /// of the `record` table, along with the name of the organisation that
+    /// operates them, as found in the ASN database at query time.
+    asn (number) {
+        /// SQLite specific hidden row
+        rowid -> BigInt,
+
+        /// Autonomous System Number, e.g. 15169.
+        number -> Integer,
+
+        /// Name of the organisation, e.g. "Google LLC".
+        organisation -> Nullable<Text>,
+
+        /// Identifier of the organisation in the `as_org` table, shared by
+        /// its sibling ASNs, as mapped by the as_org_load(8) binary.
+        org_id -> Nullable<Text>,
+    }
+}
+
+table! {
+    /// # AS Organisation
+    /// This table represents the CAIDA AS Organizations dataset, mapping each
+    /// Autonomous System to the organisation it is registered to, such that
+    /// the sibling ASNs of providers like Amazon, Google and Akamai may be
+    /// counted as one. It holds a single dataset, replaced on each load.
+    as_org (number) {
+        /// SQLite specific hidden row
+        rowid -> BigInt,
+
+        /// Autonomous System Number, e.g. 15169.
+        number -> Integer,
+
+        /// Name of the AS, e.g. "GOOGLE".
+        aut_name -> Nullable<Text>,
+
+        /// Identifier of the organisation, e.g. "GOGL-ARIN".
+        org_id -> Text,
+
+        /// Name of the organisation, e.g. "Google LLC".
+        org_name -> Nullable<Text>,
+
+        /// ISO 3166-1 code of the country the organisation is registered in.
+        country -> Nullable<Text>,
+
+        /// The RIR or WHOIS database the registration came from, e.g. "ARIN".
+        source -> Nullable<Text>,
+
+        /// File name of the dataset loaded, e.g. "20191001.as-org2info.txt".
+        dataset -> Text,
+    }
+}
+
+table! {
+    /// # Enrichment
+    /// This table represents the attribution of the addresses collected, as
+    /// recomputed after the fact by the enrich(8) binary against a supplied
+    /// MaxMind DB, leaving the DNS data as it was collected. Each row is
+    /// versioned by the type and build date of the database, such that
+    /// attribution against several builds may be compared. Columns not
+    /// provided by the type of database are `NULL`, e.g. `country` for an ASN
+    /// database.
+    enrichment (address) {
+        /// SQLite specific hidden row
+        rowid -> BigInt,
+
+        /// IP address, as stored in `record.address`.
+        address -> Text,
+
+        /// Type of the database, e.g. "GeoLite2-ASN" or "GeoLite2-City".
+        database_type -> Text,
+
+        /// Date the database was built, e.g. "2019-11-19".
+        build_date -> Text,
+
+        /// The Autonomous System Number the address is announced from.
+        asn -> Nullable<Integer>,
+
+        /// Name of the organisation operating the AS.
+        organisation -> Nullable<Text>,
+
+        /// The network the address was matched in, in CIDR notation.
+        prefix -> Nullable<Text>,
+
+        /// ISO 3166-1 code of the country the address is located in, e.g.
+        /// "GB".
+        country -> Nullable<Text>,
+
+        /// English name of the city the address is located in.
+        city -> Nullable<Text>,
+
+        /// Approximate latitude of the address.
+        latitude -> Nullable<Double>,
+
+        /// Approximate longitude of the address.
+        longitude -> Nullable<Double>,
+
+        /// Time the enrichment was performed, represented as UTC derived from
+        /// Unix Epoch.
+        enrich_time -> BigInt,
     }
 }
@@ -0,0 +1,173 @@
+use crate::asn::PrefixTrie;
+use failure::Error;
+use std::io::{BufRead, BufReader, Read};
+use std::net::IpAddr;
+use std::path::Path;
+
+/// Blocks of the IANA IPv4 and IPv6 Special-Purpose Address Registries, with
+/// the name recorded against addresses within them and whether they are
+/// globally reachable. Multicast, while allocated elsewhere, is included as it
+/// is never a valid answer.
+///
+/// See Also: [IANA IPv4 Special-Purpose Address Registry](https://www.iana.org/assignments/iana-ipv4-special-registry/)
+/// and [IANA IPv6 Special-Purpose Address Registry](https://www.iana.org/assignments/iana-ipv6-special-registry/)
+const SPECIAL_PURPOSE: &[(&str, usize, &str, bool)] = &[
+    ("0.0.0.0", 8, "this_network", false),
+    ("10.0.0.0", 8, "private", false),
+    ("100.64.0.0", 10, "shared", false),
+    ("127.0.0.0", 8, "loopback", false),
+    ("169.254.0.0", 16, "link_local", false),
+    ("172.16.0.0", 12, "private", false),
+    ("192.0.0.0", 24, "ietf_protocol", false),
+    ("192.0.0.9", 32, "port_control", true),
+    ("192.0.0.10", 32, "turn", true),
+    ("192.0.2.0", 24, "documentation", false),
+    ("192.31.196.0", 24, "as112", true),
+    ("192.52.193.0", 24, "amt", true),
+    ("192.88.99.0", 24, "6to4_relay", true),
+    ("192.168.0.0", 16, "private", false),
+    ("192.175.48.0", 24, "as112", true),
+    ("198.18.0.0", 15, "benchmarking", false),
+    ("198.51.100.0", 24, "documentation", false),
+    ("203.0.113.0", 24, "documentation", false),
+    ("224.0.0.0", 4, "multicast", false),
+    ("240.0.0.0", 4, "reserved", false),
+    ("255.255.255.255", 32, "limited_broadcast", false),
+    ("::", 128, "unspecified", false),
+    ("::1", 128, "loopback", false),
+    ("::ffff:0:0", 96, "ipv4_mapped", false),
+    ("64:ff9b::", 96, "nat64", true),
+    ("64:ff9b:1::", 48, "nat64_local", false),
+    ("100::", 64, "discard", false),
+    ("2001::", 23, "ietf_protocol", false),
+    ("2001::", 32, "teredo", true),
+    ("2001:1::1", 128, "port_control", true),
+    ("2001:1::2", 128, "turn", true),
+    ("2001:2::", 48, "benchmarking", false),
+    ("2001:3::", 32, "amt", true),
+    ("2001:4:112::", 48, "as112", true),
+    ("2001:10::", 28, "orchid", false),
+    ("2001:20::", 28, "orchid_v2", true),
+    ("2001:db8::", 32, "documentation", false),
+    ("2002::", 16, "6to4", true),
+    ("2620:4f:8000::", 48, "as112", true),
+    ("fc00::", 7, "unique_local", false),
+    ("fe80::", 10, "link_local", false),
+    ("ff00::", 8, "multicast", false),
+];
+
+/// How an address stands against the special-purpose registries and the
+/// bogon list.
+#[derive(Debug, PartialEq)]
+pub struct Classification {
+    /// Name of the special-purpose block the address is within, e.g.
+    /// "private" or "documentation"
+    pub special_purpose: Option<&'static str>,
+    /// Whether the address should never be seen as a public answer, being
+    /// either not globally reachable or on the bogon list
+    pub bogon: bool,
+}
+
+/// Classifies addresses against the IANA special-purpose registries, along
+/// with any bogon lists loaded, such as the Team Cymru full bogons.
+pub struct AddressClassifier {
+    special: PrefixTrie<(&'static str, bool)>,
+    bogons: PrefixTrie<()>,
+}
+
+impl Default for AddressClassifier {
+    fn default() -> AddressClassifier {
+        let mut special = PrefixTrie::new();
+        for (network, length, name, reachable) in SPECIAL_PURPOSE {
+            special.insert(network.parse().unwrap(), *length, (*name, *reachable));
+        }
+        AddressClassifier {
+            special,
+            bogons: PrefixTrie::new(),
+        }
+    }
+}
+
+impl AddressClassifier {
+    /// A classifier of the special-purpose registries alone.
+    pub fn new() -> AddressClassifier {
+        AddressClassifier::default()
+    }
+
+    /// Load a bogon list from a file on disk, returning the number of
+    /// prefixes loaded.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the bogon list
+    pub fn load_bogons<P: AsRef<Path>>(&mut self, path: P) -> Result<usize, Error> {
+        let file = crate::compress::open(path)?;
+        self.load_bogons_reader(file)
+    }
+
+    /// Load a bogon list of one prefix in CIDR notation per line, with lines
+    /// starting `#` ignored.
+    ///
+    /// ```
+    /// use damp::special::AddressClassifier;
+    ///
+    /// let list = "# last updated 1574186401\n1.10.0.0/21\n2001:db9::/32\n";
+    /// let mut classifier = AddressClassifier::new();
+    /// assert_eq!(classifier.load_bogons_reader(list.as_bytes()).unwrap(), 2);
+    ///
+    /// let found = classifier.classify("1.10.3.4".parse().unwrap());
+    /// assert_eq!(found.special_purpose, None);
+    /// assert!(found.bogon);
+    /// ```
+    pub fn load_bogons_reader<R: Read>(&mut self, reader: R) -> Result<usize, Error> {
+        let mut count = 0;
+        for line in BufReader::new(reader).lines() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.splitn(2, '/');
+            let network: IpAddr = match parts.next().map(str::parse) {
+                Some(Ok(n)) => n,
+                _ => return Err(failure::format_err!("Invalid bogon prefix '{}'", line)),
+            };
+            let length: usize = match parts.next().map(str::parse) {
+                Some(Ok(l)) => l,
+                _ => return Err(failure::format_err!("Invalid bogon prefix '{}'", line)),
+            };
+            self.bogons.insert(network, length, ());
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Classify the address.
+    ///
+    /// ```
+    /// use damp::special::AddressClassifier;
+    ///
+    /// let classifier = AddressClassifier::new();
+    /// let found = classifier.classify("192.168.1.1".parse().unwrap());
+    /// assert_eq!(found.special_purpose, Some("private"));
+    /// assert!(found.bogon);
+    ///
+    /// let found = classifier.classify("192.0.0.9".parse().unwrap());
+    /// assert_eq!(found.special_purpose, Some("port_control"));
+    /// assert!(!found.bogon);
+    ///
+    /// let found = classifier.classify("2001:db8::1".parse().unwrap());
+    /// assert_eq!(found.special_purpose, Some("documentation"));
+    ///
+    /// let found = classifier.classify("8.8.8.8".parse().unwrap());
+    /// assert_eq!(found.special_purpose, None);
+    /// assert!(!found.bogon);
+    /// ```
+    pub fn classify(&self, address: IpAddr) -> Classification {
+        let special = self.special.longest_match(address).map(|(_, v)| *v);
+        let listed = self.bogons.longest_match(address).is_some();
+        Classification {
+            special_purpose: special.map(|(name, _)| name),
+            bogon: listed || special.map_or(false, |(_, reachable)| !reachable),
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::HashSet;
+
+/// How the addresses answered for a name vary between repeated queries,
+/// distinguishing DNS-based load balancing from a fixed configuration.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Balancing {
+    /// No query was answered with any address.
+    Unanswered,
+    /// Every query was answered with the same, single address.
+    Single,
+    /// Every query was answered with the same addresses in the same order.
+    Static,
+    /// Every query was answered with the same addresses, but their order
+    /// changed - classic round-robin DNS.
+    RoundRobin,
+    /// The addresses themselves changed between queries, e.g. a subset of a
+    /// larger pool being handed out, or weighted/latency based answers.
+    Rotating,
+}
+
+impl Balancing {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Balancing::Unanswered => "unanswered",
+            Balancing::Single => "single",
+            Balancing::Static => "static",
+            Balancing::RoundRobin => "round_robin",
+            Balancing::Rotating => "rotating",
+        }
+    }
+
+    /// Whether the answers indicate DNS-based load balancing.
+    pub fn is_load_balanced(&self) -> bool {
+        match self {
+            Balancing::RoundRobin | Balancing::Rotating => true,
+            _ => false,
+        }
+    }
+}
+
+/// The answer to one of a series of repeated queries.
+#[derive(Clone, Debug, Default)]
+pub struct Sample {
+    /// A or AAAA addresses in the order they were answered
+    pub addresses: Vec<String>,
+    /// Lowest TTL of the addresses, if any were answered
+    pub ttl: Option<i32>,
+}
+
+/// Metrics of how the answers to repeated queries of a name vary.
+#[derive(Debug, PartialEq)]
+pub struct Stability {
+    /// Count of queries made
+    pub samples: usize,
+    /// Count of distinct address sets seen, ignoring order
+    pub distinct_sets: usize,
+    /// Count of distinct addresses seen across all queries
+    pub distinct_addresses: usize,
+    /// Largest number of addresses in a single answer
+    pub max_set_size: usize,
+    /// The same address set was seen in a different order
+    pub reordered: bool,
+    /// Lowest TTL seen
+    pub ttl_min: Option<i32>,
+    /// Highest TTL seen
+    pub ttl_max: Option<i32>,
+    /// The TTL fell from one query to the next without ever rising, as it
+    /// does when every answer is served from the same cache entry
+    pub ttl_decreasing: bool,
+    /// Classification of how the addresses vary
+    pub balancing: Balancing,
+}
+
+impl Stability {
+    /// Compute the metrics of a series of samples, given in the order they
+    /// were queried. Samples without any addresses, e.g. a `SERVFAIL`, are
+    /// counted but otherwise ignored.
+    ///
+    /// ```
+    /// use damp::stability::{Balancing, Sample, Stability};
+    ///
+    /// let sample = |a: &[&str], ttl| Sample {
+    ///     addresses: a.iter().map(|s| s.to_string()).collect(),
+    ///     ttl: Some(ttl),
+    /// };
+    /// let samples = vec![
+    ///     sample(&["192.0.2.1", "192.0.2.2"], 60),
+    ///     sample(&["192.0.2.2", "192.0.2.1"], 58),
+    /// ];
+    /// let stability = Stability::from_samples(&samples);
+    /// assert_eq!(stability.distinct_sets, 1);
+    /// assert_eq!(stability.balancing, Balancing::RoundRobin);
+    /// assert!(stability.ttl_decreasing);
+    /// ```
+    pub fn from_samples(samples: &[Sample]) -> Stability {
+        let mut sets: Vec<Vec<&String>> = Vec::new();
+        let mut addresses: HashSet<&String> = HashSet::new();
+        let mut reordered = false;
+
+        for (i, sample) in samples.iter().enumerate() {
+            let mut set: Vec<&String> = sample.addresses.iter().collect();
+            set.sort();
+            set.dedup();
+            // A failed query says nothing of how the addresses vary
+            if set.is_empty() {
+                continue;
+            }
+            if !sets.contains(&set) {
+                sets.push(set.clone());
+            }
+            addresses.extend(sample.addresses.iter());
+
+            // Compare the order against any earlier sample of the same set
+            reordered |= samples[..i].iter().any(|earlier| {
+                let mut earlier_set: Vec<&String> = earlier.addresses.iter().collect();
+                earlier_set.sort();
+                earlier_set.dedup();
+                earlier_set == set && earlier.addresses != sample.addresses
+            });
+        }
+
+        let ttls: Vec<i32> = samples.iter().filter_map(|s| s.ttl).collect();
+        let ttl_decreasing = ttls.len() > 1
+            && ttls.windows(2).all(|w| w[1] <= w[0])
+            && ttls.windows(2).any(|w| w[1] < w[0]);
+
+        let max_set_size = sets.iter().map(Vec::len).max().unwrap_or(0);
+        let balancing = if addresses.is_empty() {
+            Balancing::Unanswered
+        } else if sets.len() > 1 {
+            Balancing::Rotating
+        } else if reordered {
+            Balancing::RoundRobin
+        } else if max_set_size == 1 {
+            Balancing::Single
+        } else {
+            Balancing::Static
+        };
+
+        Stability {
+            samples: samples.len(),
+            distinct_sets: sets.len(),
+            distinct_addresses: addresses.len(),
+            max_set_size,
+            reordered,
+            ttl_min: ttls.iter().cloned().min(),
+            ttl_max: ttls.iter().cloned().max(),
+            ttl_decreasing,
+            balancing,
+        }
+    }
+}
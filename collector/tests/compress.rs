@@ -0,0 +1,62 @@
+//! Reading real archives and compressed files from `tests/fixtures/compress`,
+//! each holding `list.csv` - some alongside a `LICENSE.txt` before it.
+
+use damp::compress;
+use std::fs;
+use std::io::Read;
+
+const FIXTURES: &str = "tests/fixtures/compress";
+
+fn read(file: &str, suffix: &str) -> String {
+    let mut contents = String::new();
+    compress::open_member(format!("{}/{}", FIXTURES, file), suffix)
+        .unwrap()
+        .read_to_string(&mut contents)
+        .unwrap();
+    contents
+}
+
+fn list() -> String {
+    fs::read_to_string(format!("{}/list.csv", FIXTURES)).unwrap()
+}
+
+#[test]
+fn compressed_files() {
+    for file in &["list.csv.gz", "list.csv.bz2", "list.csv.xz", "list.csv.zst"] {
+        assert_eq!(read(file, ""), list(), "{}", file);
+    }
+}
+
+#[test]
+fn uncompressed_file() {
+    assert_eq!(read("list.csv", ".csv"), list());
+}
+
+#[test]
+fn zip64_member() {
+    assert_eq!(read("list.zip", ".csv"), list());
+    assert_eq!(read("list.zip", ""), "Licence of the list\n");
+}
+
+#[test]
+fn zip_stored_with_data_descriptor() {
+    assert_eq!(read("streamed.zip", ".csv"), list());
+}
+
+#[test]
+fn tar_gnu_long_name() {
+    assert_eq!(read("list.tar.gz", ".csv"), list());
+}
+
+#[test]
+fn tar_pax_long_name() {
+    assert_eq!(read("list.tar.xz", ".csv"), list());
+}
+
+#[test]
+fn missing_member() {
+    let path = format!("{}/list.zip", FIXTURES);
+    assert!(compress::open_member(&path, ".mmdb").is_err());
+    let path = format!("{}/list.tar.gz", FIXTURES);
+    assert!(compress::open_member(&path, ".mmdb").is_err());
+}
@@ -0,0 +1,91 @@
+//! Validation against locally signed zones, generated by
+//! `tests/fixtures/dnssec/sign.py` which describes each of them.
+
+use damp::dnssec::{dnssec_type, RecordStore, Status, Validator};
+use std::fs;
+use trust_dns::rr::{Name, Record, RecordType};
+use trust_dns_proto::rr::dnssec::rdata::DNSSECRecordType;
+use trust_dns_proto::serialize::binary::BinDecodable;
+
+/// Between the inception and expiration of every signature, save those of
+/// expired.test.
+const VALIDATION_TIME: u32 = 1_580_000_000;
+
+fn fixture(file: &str) -> String {
+    fs::read_to_string(format!("tests/fixtures/dnssec/{}", file)).unwrap()
+}
+
+fn load_store() -> RecordStore {
+    let mut store = RecordStore::new();
+    for line in fixture("records.txt").lines() {
+        let fields: Vec<&str> = line.split(' ').collect();
+        let name = Name::from_ascii(fields[0]).unwrap();
+        let query_type = match fields[1] {
+            "DS" => dnssec_type(DNSSECRecordType::DS),
+            "DNSKEY" => dnssec_type(DNSSECRecordType::DNSKEY),
+            _ => RecordType::SOA,
+        };
+        let bytes: Vec<u8> = (0..fields[2].len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&fields[2][i..i + 2], 16).unwrap())
+            .collect();
+        store.insert(&name, query_type, Record::from_bytes(&bytes).unwrap());
+    }
+    store
+}
+
+fn assert_status(apex: &str, status: Status) {
+    let validator = Validator::from_reader(fixture("anchor.txt").as_bytes()).unwrap();
+    let name = Name::from_ascii(apex).unwrap();
+    let validation = validator.validate(&load_store(), &name, VALIDATION_TIME);
+    assert_eq!(validation.status, status, "{}", validation.reason);
+}
+
+#[test]
+fn valid_signatures_are_secure() {
+    assert_status("test", Status::Secure);
+    assert_status("secure.test", Status::Secure);
+}
+
+#[test]
+fn expired_signatures_are_bogus() {
+    assert_status("expired.test", Status::Bogus);
+}
+
+#[test]
+fn ds_matching_no_dnskey_is_bogus() {
+    assert_status("broken.test", Status::Bogus);
+}
+
+#[test]
+fn missing_ds_proven_by_nsec_is_insecure() {
+    assert_status("insecure.test", Status::Insecure);
+    assert_status("gone.test", Status::Insecure);
+}
+
+#[test]
+fn unsigned_cut_above_apex_is_insecure() {
+    assert_status("www.cut.test", Status::Insecure);
+}
+
+#[test]
+fn nsec_not_denying_ds_is_bogus() {
+    assert_status("uncovered.test", Status::Bogus);
+    assert_status("forged.test", Status::Bogus);
+}
+
+#[test]
+fn missing_ds_proven_by_nsec3_is_insecure() {
+    assert_status("matched.opt", Status::Insecure);
+    assert_status("unsigned.opt", Status::Insecure);
+}
+
+#[test]
+fn nsec3_without_opt_out_is_bogus() {
+    assert_status("absent.strict", Status::Bogus);
+}
+
+#[test]
+fn uncollected_zone_is_indeterminate() {
+    assert_status("missing.example", Status::Indeterminate);
+}
@@ -0,0 +1,260 @@
+#!/usr/bin/env python3
+"""Generate the locally signed zones used by tests/dnssec.rs.
+
+Writes records.txt, one collected record per line as "<query name> <query
+type> <hex wire format>" - the same data dnssec_validate(8) loads from the
+dnssec_record table - and anchor.txt, the DS of the root key. Every zone is
+signed with a single ECDSAP256SHA256 key, so that each RRset holds one record.
+
+Zones:
+* .             - root, delegating securely to test., opt. and strict.
+* test.         - NSEC signed
+  * secure.test.    signed, with a valid DS
+  * expired.test.   signed, every signature expired
+  * broken.test.    signed, the DS digest matching no DNSKEY
+  * insecure.test.  unsigned, an NSEC at the delegation proving no DS
+  * gone.test.      does not exist, an NSEC covering it
+  * uncovered.test. no DS, with an NSEC that does not cover the name
+  * forged.test.    no DS, with an NSEC whose bitmap lists DS
+  * cut.test.       unsigned, an NSEC at the delegation proving no DS, validated
+                    as the zone cut above www.cut.test.
+* opt.          - NSEC3 signed with opt-out
+  * matched.opt.    unsigned, a matching NSEC3 proving no DS
+  * unsigned.opt.   unsigned, skipped by opt-out
+* strict.       - NSEC3 signed without opt-out
+  * absent.strict.  no DS, covered by an NSEC3 without opt-out
+
+Requires the `cryptography` package. Signatures are randomised, so the output
+differs on every run.
+"""
+
+import base64
+import hashlib
+import struct
+
+from cryptography.hazmat.primitives import hashes
+from cryptography.hazmat.primitives.asymmetric import ec
+from cryptography.hazmat.primitives.asymmetric.utils import decode_dss_signature
+
+INCEPTION = 1577836800  # 2020-01-01
+EXPIRATION = 1893456000  # 2030-01-01
+EXPIRED = 1578614400  # 2020-01-10, before the tests' validation time
+TTL = 3600
+
+NS, SOA, DS, RRSIG, NSEC, DNSKEY, NSEC3 = 2, 6, 43, 46, 47, 48, 50
+ALGORITHM = 13
+
+
+def wire_name(name):
+    labels = [l for l in name.lower().split(".") if l]
+    return b"".join(bytes([len(l)]) + l.encode() for l in labels) + b"\x00"
+
+
+def label_count(name):
+    return len([l for l in name.split(".") if l])
+
+
+def type_bitmap(types):
+    windows = {}
+    for t in types:
+        windows.setdefault(t >> 8, set()).add(t & 0xFF)
+    out = b""
+    for window in sorted(windows):
+        bits = windows[window]
+        length = max(bits) // 8 + 1
+        bitmap = bytearray(length)
+        for b in bits:
+            bitmap[b // 8] |= 0x80 >> (b % 8)
+        out += bytes([window, length]) + bytes(bitmap)
+    return out
+
+
+def record(name, rtype, rdata, ttl=TTL):
+    return wire_name(name) + struct.pack("!HHIH", rtype, 1, ttl, len(rdata)) + rdata
+
+
+class Key:
+    def __init__(self, zone):
+        self.zone = zone
+        self.private = ec.generate_private_key(ec.SECP256R1())
+        numbers = self.private.public_key().public_numbers()
+        public = numbers.x.to_bytes(32, "big") + numbers.y.to_bytes(32, "big")
+        self.rdata = struct.pack("!HBB", 257, 3, ALGORITHM) + public
+
+    def tag(self):
+        total = 0
+        for i, b in enumerate(self.rdata):
+            total += b if i & 1 else b << 8
+        total += (total >> 16) & 0xFFFF
+        return total & 0xFFFF
+
+    def ds(self, digest=None):
+        if digest is None:
+            digest = hashlib.sha256(wire_name(self.zone) + self.rdata).digest()
+        return struct.pack("!HBB", self.tag(), ALGORITHM, 2) + digest
+
+    def sign(self, owner, rtype, rdata, expiration=EXPIRATION):
+        header = struct.pack(
+            "!HBBIIIH",
+            rtype,
+            ALGORITHM,
+            label_count(owner),
+            TTL,
+            expiration,
+            INCEPTION,
+            self.tag(),
+        ) + wire_name(self.zone)
+        data = header + record(owner, rtype, rdata)
+        r, s = decode_dss_signature(self.private.sign(data, ec.ECDSA(hashes.SHA256())))
+        signature = r.to_bytes(32, "big") + s.to_bytes(32, "big")
+        return record(owner, RRSIG, header + signature)
+
+
+def nsec3_hash(name, salt, iterations):
+    digest = hashlib.sha1(wire_name(name) + salt).digest()
+    for _ in range(iterations):
+        digest = hashlib.sha1(digest + salt).digest()
+    return digest
+
+
+def base32hex(data):
+    standard = base64.b32encode(data).decode().rstrip("=")
+    table = str.maketrans(
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZ234567", "0123456789abcdefghijklmnopqrstuv"
+    )
+    return standard.translate(table)
+
+
+def nsec3_chain(zone, names, salt, iterations, opt_out):
+    """NSEC3 records of the zone for the names and their type bitmaps"""
+    hashed = sorted((nsec3_hash(n, salt, iterations), n, t) for n, t in names)
+    chain = {}
+    for i, (digest, name, types) in enumerate(hashed):
+        next_digest = hashed[(i + 1) % len(hashed)][0]
+        owner = base32hex(digest) + "." + zone
+        rdata = (
+            struct.pack("!BBHB", 1, 1 if opt_out else 0, iterations, len(salt))
+            + salt
+            + bytes([len(next_digest)])
+            + next_digest
+            + type_bitmap(types)
+        )
+        chain[name] = (owner, rdata, digest)
+    return chain
+
+
+def nsec3_covering(chain, name, salt, iterations):
+    digest = nsec3_hash(name, salt, iterations)
+    entries = sorted(chain.values(), key=lambda e: e[2])
+    for i, (owner, rdata, owner_digest) in enumerate(entries):
+        next_digest = entries[(i + 1) % len(entries)][2]
+        if owner_digest < next_digest:
+            if owner_digest < digest < next_digest:
+                return owner, rdata
+        elif digest > owner_digest or digest < next_digest:
+            return owner, rdata
+    raise ValueError("no NSEC3 covers " + name)
+
+
+def soa(zone):
+    return (
+        wire_name("ns." + zone)
+        + wire_name("hostmaster." + zone)
+        + struct.pack("!IIIII", 1, 7200, 3600, 1209600, 3600)
+    )
+
+
+def main():
+    lines = []
+
+    def collect(query_name, query_type, records):
+        name = query_name.rstrip(".") or "."
+        for r in records:
+            lines.append("%s %s %s" % (name, query_type, r.hex()))
+
+    def signed(key, owner, rtype, rdata, expiration=EXPIRATION):
+        return [record(owner, rtype, rdata), key.sign(owner, rtype, rdata, expiration)]
+
+    def apex(key, expiration=EXPIRATION):
+        collect(key.zone, "DNSKEY", signed(key, key.zone, DNSKEY, key.rdata, expiration))
+        collect(key.zone, "SOA", signed(key, key.zone, SOA, soa(key.zone), expiration))
+
+    root = Key(".")
+    apex(root)
+    tlds = {zone: Key(zone) for zone in ("test.", "opt.", "strict.")}
+    for zone, key in tlds.items():
+        collect(zone, "DS", signed(root, zone, DS, key.ds()))
+        apex(key)
+    test, opt, strict = tlds["test."], tlds["opt."], tlds["strict."]
+
+    # test. - signed children with a DS, unsigned ones proven by NSEC
+    secure, expired, broken = Key("secure.test."), Key("expired.test."), Key("broken.test.")
+    collect("secure.test.", "DS", signed(test, "secure.test.", DS, secure.ds()))
+    apex(secure)
+    collect("expired.test.", "DS", signed(test, "expired.test.", DS, expired.ds()))
+    apex(expired, EXPIRED)
+    collect("broken.test.", "DS", signed(test, "broken.test.", DS, broken.ds(b"\x00" * 32)))
+    apex(broken)
+
+    delegation = [NS, RRSIG, NSEC]
+    nsec = {
+        "expired.test.": ("insecure.test.", delegation + [DS]),
+        "insecure.test.": ("secure.test.", delegation),
+        "forged.test.": ("gone.test.", delegation + [DS]),
+        "cut.test.": ("expired.test.", delegation),
+    }
+
+    def nsec_proof(owner):
+        next_name, types = nsec[owner]
+        return signed(test, owner, NSEC, wire_name(next_name) + type_bitmap(types))
+
+    collect("insecure.test.", "DS", nsec_proof("insecure.test."))
+    collect("gone.test.", "DS", nsec_proof("expired.test."))
+    collect("uncovered.test.", "DS", nsec_proof("insecure.test."))
+    collect("forged.test.", "DS", nsec_proof("forged.test."))
+    collect("cut.test.", "DS", nsec_proof("cut.test."))
+
+    # opt. - NSEC3 with opt-out, matched.opt. in the chain and unsigned.opt. not
+    salt, iterations = bytes.fromhex("aabbccdd"), 5
+    chain = nsec3_chain(
+        "opt.",
+        [("opt.", [NS, SOA, RRSIG, DNSKEY, NSEC3]), ("matched.opt.", [NS])],
+        salt,
+        iterations,
+        True,
+    )
+    owner, rdata, _ = chain["matched.opt."]
+    collect("matched.opt.", "DS", signed(opt, owner, NSEC3, rdata))
+    proof = []
+    owner, rdata, _ = chain["opt."]
+    proof += signed(opt, owner, NSEC3, rdata)
+    covering = nsec3_covering(chain, "unsigned.opt.", salt, iterations)
+    if covering[0] != owner:
+        proof += signed(opt, covering[0], NSEC3, covering[1])
+    collect("unsigned.opt.", "DS", proof)
+
+    # strict. - NSEC3 without opt-out, so absent.strict. cannot be insecure
+    chain = nsec3_chain(
+        "strict.",
+        [("strict.", [NS, SOA, RRSIG, DNSKEY, NSEC3]), ("other.strict.", [NS, DS])],
+        b"",
+        0,
+        False,
+    )
+    proof = []
+    owner, rdata, _ = chain["strict."]
+    proof += signed(strict, owner, NSEC3, rdata)
+    covering = nsec3_covering(chain, "absent.strict.", b"", 0)
+    if covering[0] != owner:
+        proof += signed(strict, covering[0], NSEC3, covering[1])
+    collect("absent.strict.", "DS", proof)
+
+    with open("records.txt", "w") as f:
+        f.write("\n".join(lines) + "\n")
+    with open("anchor.txt", "w") as f:
+        ds = root.ds()
+        f.write(". IN DS %d %d 2 %s\n" % (root.tag(), ALGORITHM, ds[4:].hex().upper()))
+
+
+if __name__ == "__main__":
+    main()